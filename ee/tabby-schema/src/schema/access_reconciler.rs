@@ -0,0 +1,39 @@
+use async_trait::async_trait;
+use juniper::{GraphQLEnum, GraphQLObject};
+
+use super::Result;
+
+/// One primitive change the reconciler applies (or, in a dry run, would apply) to bring user
+/// groups, memberships, source-id read grants, and active preset documents in line with the
+/// declarative document.
+#[derive(GraphQLEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessPolicyChangeKind {
+    CreateUserGroup,
+    DeleteUserGroup,
+    UpsertMembership,
+    DeleteMembership,
+    GrantSourceRead,
+    RevokeSourceRead,
+    SetPresetDocumentActive,
+}
+
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct AccessPolicyChange {
+    pub kind: AccessPolicyChangeKind,
+    pub description: String,
+}
+
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct AccessPolicyReconciliationResult {
+    pub dry_run: bool,
+    pub changes: Vec<AccessPolicyChange>,
+}
+
+/// Reconciles `user_group()`/`access_policy()` state against a versioned declarative document
+/// (groups, memberships, source-id read grants, active preset documents), the same way a
+/// startup reconciliation pass would. The admin `reconcile_access_policy` mutation is a manual
+/// trigger for the same computation.
+#[async_trait]
+pub trait AccessPolicyReconciler: Send + Sync {
+    async fn reconcile(&self, dry_run: bool) -> Result<AccessPolicyReconciliationResult>;
+}
@@ -0,0 +1,110 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use juniper::{GraphQLEnum, GraphQLObject, ID};
+
+use super::Result;
+use crate::juniper::relay::{Connection, NodeType};
+
+/// The kind of privileged mutation an `AuditLog` entry was recorded for.
+#[derive(GraphQLEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuditAction {
+    SecuritySettingUpdated,
+    NetworkSettingUpdated,
+    EmailSettingUpdated,
+    OAuthCredentialUpdated,
+    OidcCredentialUpdated,
+    LdapCredentialUpdated,
+    InvitationCreated,
+    InvitationDeleted,
+    IntegrationCreated,
+    IntegrationUpdated,
+    IntegrationDeleted,
+    UserRoleUpdated,
+    UserActiveUpdated,
+    PresetDocumentActiveSet,
+    LicenseUpdated,
+    BackupCreated,
+    BackupRestored,
+    UserGroupDeleted,
+    PageDeleted,
+    SourceReadAccessGranted,
+    AccessPolicyReconciled,
+    RegistrationTokenReset,
+    PasswordResetUrlGenerated,
+    GitRepositoryCreated,
+    GitRepositoryUpdated,
+    GitRepositoryDeleted,
+    EmailSettingDeleted,
+    CaptchaSettingUpdated,
+    CaptchaSettingDeleted,
+    BackupRestoreRequested,
+    LicenseReset,
+    IntegratedRepositoryActiveUpdated,
+    JobRunTriggered,
+    CustomDocumentCreated,
+    CustomDocumentDeleted,
+    UserGroupCreated,
+    SourceReadAccessRevoked,
+}
+
+/// An immutable record of a single privileged mutation: admin-gated, or gated by the policy
+/// engine (page deletion, source read-access grants and revokes).
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct AuditLog {
+    pub id: ID,
+    pub actor_id: ID,
+    pub action: AuditAction,
+    pub target_id: Option<String>,
+
+    /// A redacted, human-readable summary of what changed; never contains secrets.
+    pub summary: String,
+
+    /// Structured, redacted detail about the change (e.g. the old/new value), serialized as
+    /// JSON text. `None` when `summary` alone is sufficient.
+    pub metadata: Option<String>,
+    pub source_ip: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl NodeType for AuditLog {
+    type Cursor = String;
+
+    fn cursor(&self) -> Self::Cursor {
+        self.id.to_string()
+    }
+
+    fn connection_type_name() -> &'static str {
+        "AuditLogConnection"
+    }
+
+    fn edge_type_name() -> &'static str {
+        "AuditLogEdge"
+    }
+}
+
+#[async_trait]
+pub trait AuditLogService: Send + Sync {
+    /// Append a new audit entry. Called by resolvers after a privileged mutation succeeds, and
+    /// also after one fails when the mutation reports through `fire_mutation_hook`.
+    async fn record(
+        &self,
+        actor_id: &ID,
+        action: AuditAction,
+        target_id: Option<String>,
+        summary: String,
+        metadata: Option<String>,
+        source_ip: Option<String>,
+    ) -> Result<()>;
+
+    async fn list(
+        &self,
+        actors: Option<Vec<ID>>,
+        actions: Option<Vec<AuditAction>>,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<usize>,
+        last: Option<usize>,
+    ) -> Result<Connection<AuditLog>>;
+}
@@ -0,0 +1,48 @@
+use std::marker::PhantomData;
+
+use juniper::ID;
+
+use super::page::{self, PageService};
+use super::policy_engine::PolicyResource;
+use super::{check_policy, check_user, Context, Result};
+
+/// A zero-sized proof that the policy check for `Op` has already run, required by every
+/// mutating `PageService` method so a future resolver can't call a write method without first
+/// producing one — "forgot to check the policy" becomes a compile error instead of a runtime
+/// bug. `mint` is private to this module, so [`authorize_update_page`] is the only way to
+/// produce one; nothing outside this file, not even the rest of `tabby-schema`, can mint its
+/// own proof and skip the check.
+pub struct Authorized<Op> {
+    _op: PhantomData<Op>,
+}
+
+impl<Op> Authorized<Op> {
+    fn mint() -> Self {
+        Self { _op: PhantomData }
+    }
+}
+
+/// Marker type for the capability proven by the `"update_page"` policy check.
+pub struct UpdatePage;
+
+/// Check the caller out of `check_user` and `check_policy("update_page")` in one call, returning
+/// the page alongside the [`Authorized<UpdatePage>`] proof every `PageService` write method
+/// needs. `check_policy` is the sole authorization decision here -- it is not ANDed with a
+/// hardcoded ownership check, so a `PolicyEngine` can actually override the default behavior
+/// (the built-in engine's default implementation is what re-derives the old `author_id ==
+/// caller` rule; see the doc comment on `PolicyEngine`).
+pub(crate) async fn authorize_update_page(
+    ctx: &Context,
+    page_service: &dyn PageService,
+    page_id: &ID,
+) -> Result<(page::Page, Authorized<UpdatePage>)> {
+    check_user(ctx).await?;
+    let page = page_service.get(page_id).await?;
+    check_policy(
+        ctx,
+        "update_page",
+        PolicyResource::new().with("author_id", page.author_id.to_string()),
+    )
+    .await?;
+    Ok((page, Authorized::mint()))
+}
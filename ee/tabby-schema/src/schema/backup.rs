@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use juniper::{GraphQLObject, ID};
+
+use super::Result;
+
+/// A single point-in-time snapshot of the state database, written to the configured backup
+/// directory.
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct BackupArchive {
+    pub id: ID,
+    pub path: String,
+    pub size_bytes: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait BackupService: Send + Sync {
+    /// Quiesce writers and copy the state database to a new timestamped archive.
+    async fn create(&self) -> Result<BackupArchive>;
+
+    async fn list(&self) -> Result<Vec<BackupArchive>>;
+
+    /// A short-lived signed URL the admin can download the archive from, rather than leaving
+    /// it on disk.
+    async fn download_url(&self, id: &ID) -> Result<String>;
+
+    /// Issue a one-time confirmation token for restoring `id`, so a single misclick can't wipe
+    /// the current state.
+    async fn request_restore(&self, id: &ID) -> Result<String>;
+
+    /// Restore `id`, provided `confirmation_token` matches the one from `request_restore`.
+    async fn restore(&self, id: &ID, confirmation_token: &str) -> Result<()>;
+}
@@ -0,0 +1,43 @@
+use async_trait::async_trait;
+use juniper::{GraphQLInputObject, GraphQLObject};
+use validator::Validate;
+
+use super::Result;
+
+/// A self-hosted CAPTCHA challenge returned by `get_captcha`: a distorted image of a short
+/// random string (and, when the backing service can render one, a spoken-letters clip for
+/// accessibility), plus the `uuid` `register` must echo back alongside the user's answer.
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct CaptchaChallenge {
+    pub uuid: String,
+    pub png_base64: String,
+    pub wav_base64: Option<String>,
+}
+
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct CaptchaSetting {
+    pub require_captcha: bool,
+}
+
+#[derive(GraphQLInputObject, Validate, Clone, Debug)]
+pub struct CaptchaSettingInput {
+    pub require_captcha: bool,
+}
+
+#[async_trait]
+pub trait CaptchaService: Send + Sync {
+    async fn read_setting(&self) -> Result<Option<CaptchaSetting>>;
+    async fn update_setting(&self, input: CaptchaSettingInput) -> Result<()>;
+    async fn delete_setting(&self) -> Result<()>;
+
+    /// Render a new challenge and remember its answer (single-use, ~10 minute TTL) for a later
+    /// `verify_challenge` call.
+    async fn generate_challenge(&self) -> Result<CaptchaChallenge>;
+
+    /// Verify `answer` against the challenge `uuid`, case-insensitively. The entry is consumed
+    /// on first use whether or not it matches, so a replayed uuid always fails.
+    ///
+    /// Returns `Ok(())` unconditionally when `require_captcha` is currently disabled, so callers
+    /// (e.g. `register`) can call this without special-casing the disabled state.
+    async fn verify_challenge(&self, uuid: Option<String>, answer: Option<String>) -> Result<()>;
+}
@@ -0,0 +1,40 @@
+use async_trait::async_trait;
+
+use super::Result;
+
+#[derive(Clone, Debug)]
+pub enum MutationOutcome {
+    Success,
+    Error(String),
+}
+
+/// A completed write operation, as seen by every registered mutation hook.
+#[derive(Clone, Debug)]
+pub struct MutationEvent {
+    pub actor_id: String,
+    pub operation: &'static str,
+    pub target_ids: Vec<String>,
+    pub outcome: MutationOutcome,
+}
+
+/// One listener fired for a sensitive mutation (`delete_page`, `delete_user_group`,
+/// `grant_source_id_read_access`, ...). The outbound webhook dispatcher is the built-in
+/// consumer; nothing here stops another hook (e.g. a Slack notifier) from being registered
+/// alongside it.
+#[async_trait]
+pub trait MutationHook: Send + Sync {
+    async fn handle(&self, event: &MutationEvent) -> Result<()>;
+}
+
+/// Fans a `MutationEvent` out to every registered `MutationHook`. What's registered, and in
+/// what order, is decided wherever the `ServiceLocator` is constructed -- this crate only
+/// declares the contract, the same way it does for every other `*Service` trait here.
+///
+/// This only fires after a mutation has already run to completion -- the resolver runs the
+/// fallible operation itself and passes its `Result` to `fire_mutation_hook` (mod.rs), so both
+/// `MutationOutcome::Success` and `MutationOutcome::Error` are reachable. There is no
+/// pre-mutation hook.
+#[async_trait]
+pub trait MutationHookRegistry: Send + Sync {
+    async fn fire(&self, event: MutationEvent);
+}
@@ -1,28 +1,47 @@
 pub mod access_policy;
+pub mod access_reconciler;
 pub mod analytic;
+pub mod audit;
 pub mod auth;
+pub mod authz;
+pub mod backup;
+pub mod captcha;
 pub mod constants;
 pub mod context;
 pub mod email;
+pub mod hooks;
 pub mod ingestion;
 pub mod integration;
 pub mod interface;
 pub mod job;
 pub mod license;
 pub mod notification;
+pub mod oidc;
 pub mod page;
+pub mod page_batch;
+pub mod page_events;
+pub mod policy_engine;
 pub mod repository;
 pub mod retrieval;
 pub mod setting;
 pub mod thread;
+pub mod two_factor;
 pub mod user_event;
 pub mod user_group;
 pub mod web_documents;
 pub mod worker;
 
-use std::{sync::Arc, time::Instant};
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
 use access_policy::{AccessPolicyService, SourceIdAccessPolicy};
+use access_reconciler::{AccessPolicyReconciler, AccessPolicyReconciliationResult};
 use async_openai_alt::{
     error::OpenAIError,
     types::{
@@ -31,28 +50,39 @@ use async_openai_alt::{
         ChatCompletionRequestUserMessageContent, CreateChatCompletionRequestArgs,
     },
 };
+use audit::{AuditAction, AuditLog, AuditLogService};
 use auth::{
     AuthProvider, AuthProviderKind, AuthenticationService, Invitation, LdapCredential,
     RefreshTokenResponse, RegisterResponse, TokenAuthResponse, UpdateLdapCredentialInput,
     UserSecured,
 };
+use authz::{authorize_update_page, Authorized, UpdatePage};
+use backup::{BackupArchive, BackupService};
 use base64::Engine;
+use captcha::{CaptchaChallenge, CaptchaService, CaptchaSetting, CaptchaSettingInput};
 use chrono::{DateTime, Utc};
 use context::{ContextInfo, ContextService};
 use futures::StreamExt;
+use hooks::{MutationEvent, MutationHookRegistry, MutationOutcome};
 use interface::UserValue;
 use job::{JobRun, JobService};
 use juniper::{
-    graphql_object, graphql_subscription, graphql_value, FieldError, GraphQLEnum, GraphQLObject,
-    IntoFieldError, Object, RootNode, ScalarValue, Value, ID,
+    graphql_object, graphql_subscription, FieldError, GraphQLEnum, GraphQLInputObject,
+    GraphQLObject, IntoFieldError, Object, RootNode, ScalarValue, Value, ID,
 };
 use ldap3::result::LdapError;
 use notification::NotificationService;
+use oidc::{OidcCredential, OidcService, UpdateOidcCredentialInput};
 use page::{
     CreatePageRunInput, CreatePageSectionRunInput, CreateThreadToPageRunInput, PageRunStream,
     SectionRunStream, ThreadToPageRunStream, UpdatePageContentInput, UpdatePageSectionContentInput,
     UpdatePageSectionTitleInput, UpdatePageTitleInput,
 };
+use page_batch::{
+    BatchSectionOperationInput, BatchSectionOperationKind, BatchSectionOperationResult,
+};
+use page_events::{PageEvent, PageEventBroker, PageEventKind, PageEventStream};
+use policy_engine::{PolicyAction, PolicyDecision, PolicyEngine, PolicyResource, PolicySubject};
 use repository::RepositoryGrepOutput;
 use strum::IntoEnumIterator;
 use tabby_common::{
@@ -63,7 +93,11 @@ use tabby_inference::{
     ChatCompletionStream, CompletionOptionsBuilder, CompletionStream, Embedding as EmbeddingService,
 };
 use thread::{CreateThreadAndRunInput, CreateThreadRunInput, ThreadRunStream, ThreadService};
-use tracing::{error, warn};
+use tokio::time::timeout;
+use tracing::{error, warn, Instrument};
+use two_factor::{
+    TokenAuthOutcome, TokenAuthResult, TotpRecoveryCodes, TotpSecret, UpdateSecuritySettingResult,
+};
 use user_group::{
     CreateUserGroupInput, UpsertUserGroupMembershipInput, UserGroup, UserGroupService,
 };
@@ -122,11 +156,64 @@ pub trait ServiceLocator: Send + Sync {
     fn user_group(&self) -> Arc<dyn UserGroupService>;
     fn access_policy(&self) -> Arc<dyn AccessPolicyService>;
     fn notification(&self) -> Arc<dyn NotificationService>;
+    fn audit(&self) -> Arc<dyn AuditLogService>;
+    fn captcha(&self) -> Arc<dyn CaptchaService>;
+    fn oidc(&self) -> Arc<dyn OidcService>;
+    fn backup(&self) -> Arc<dyn BackupService>;
+    fn policy_engine(&self) -> Arc<dyn PolicyEngine>;
+    fn mutation_hooks(&self) -> Arc<dyn MutationHookRegistry>;
+    fn access_reconciler(&self) -> Arc<dyn AccessPolicyReconciler>;
+    fn page_events(&self) -> Arc<dyn PageEventBroker>;
+}
+
+/// Correlates every service call made within a single GraphQL request, so a user-reported
+/// error id maps directly onto one tree of server logs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RequestId(u64);
+
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+impl RequestId {
+    fn generate() -> Self {
+        Self(NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "req-{:x}", self.0)
+    }
+}
+
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: RequestId;
 }
 
 pub struct Context {
     pub claims: Option<auth::JWTPayload>,
-    pub locator: Arc<dyn ServiceLocator>,
+    // Private so the only way code outside this module can reach a service is through
+    // `AdminCtx`/`UserCtx`/`AuthTokenCtx::locator()` -- see the doc comment on `AdminCtx`.
+    locator: Arc<dyn ServiceLocator>,
+    pub request_id: RequestId,
+    /// The client's address, as seen by whatever's terminating the connection (reverse proxy,
+    /// HTTP server, ...). `None` when the caller couldn't determine one. Recorded on every
+    /// `AuditLog` entry so a privileged mutation can be traced back to where it came from.
+    pub source_ip: Option<String>,
+}
+
+impl Context {
+    pub fn new(
+        claims: Option<auth::JWTPayload>,
+        locator: Arc<dyn ServiceLocator>,
+        source_ip: Option<String>,
+    ) -> Self {
+        Self {
+            claims,
+            locator,
+            request_id: RequestId::generate(),
+            source_ip,
+        }
+    }
 }
 
 // To make our context usable by Juniper, we have to implement a marker trait.
@@ -134,6 +221,62 @@ impl juniper::Context for Context {}
 
 pub type Result<T, E = CoreError> = std::result::Result<T, E>;
 
+/// Span covering one resolver call plus every downstream `ctx.locator.*` service call made
+/// while it's entered, so nested service spans render as one request-scoped tree in logs.
+///
+/// That tree is only as good as whatever `tracing-subscriber` layer the binary installs; a
+/// forest/hierarchical layer (e.g. `tracing-tree`) belongs in the `tabby` binary crate's
+/// startup code next to its `Registry` setup, not in `tabby-schema`, which has no `main` and
+/// never installs a global subscriber itself.
+fn request_span(ctx: &Context, operation: &'static str) -> tracing::Span {
+    let actor = ctx.claims.as_ref().map(|c| c.sub.clone());
+    tracing::info_span!(
+        "graphql_resolver",
+        operation,
+        request_id = %ctx.request_id,
+        actor = actor.as_deref().unwrap_or("anonymous"),
+    )
+}
+
+/// Whether the `networkSetting.verboseTracing` admin toggle is on, gating the extra per-call
+/// debug logging `instrumented` emits on success (failures are always logged).
+async fn tracing_verbosity_enabled(ctx: &Context) -> bool {
+    ctx.locator
+        .setting()
+        .read_network_setting()
+        .await
+        .map(|setting| setting.verbose_tracing)
+        .unwrap_or(false)
+}
+
+/// Wrap a resolver body so it runs inside its own request span, so `CoreError`'s
+/// `IntoFieldError` impl can recover the correlation id via `CURRENT_REQUEST_ID`, and so its
+/// outcome (ok, or err with the `CoreError` message) is logged under that span.
+async fn instrumented<T>(
+    ctx: &Context,
+    operation: &'static str,
+    fut: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    let span = request_span(ctx, operation);
+    let verbose = tracing_verbosity_enabled(ctx).await;
+    let result = CURRENT_REQUEST_ID
+        .scope(ctx.request_id, fut.instrument(span.clone()))
+        .await;
+
+    let _entered = span.enter();
+    match &result {
+        Ok(_) if verbose => {
+            tracing::debug!(request_id = %ctx.request_id, operation, outcome = "ok");
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::warn!(request_id = %ctx.request_id, operation, outcome = "err", error = %e);
+        }
+    }
+
+    result
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum CoreError {
     #[error("{0}")]
@@ -167,14 +310,38 @@ impl From<LdapError> for CoreError {
     }
 }
 
+fn current_request_id() -> Option<String> {
+    CURRENT_REQUEST_ID.try_with(|id| id.to_string()).ok()
+}
+
+fn denial_field_error<S: ScalarValue>(
+    msg: &'static str,
+    code: &'static str,
+    request_id: &str,
+) -> FieldError<S> {
+    let mut ext = Object::with_capacity(2);
+    ext.add_field("code", Value::scalar(code));
+    ext.add_field("requestId", Value::scalar(request_id.to_string()));
+    FieldError::new(msg, ext.into())
+}
+
 impl<S: ScalarValue> IntoFieldError<S> for CoreError {
     fn into_field_error(self) -> FieldError<S> {
+        let request_id = current_request_id();
+        let request_id = request_id.as_deref().unwrap_or("-");
         match self {
-            Self::Forbidden(msg) => FieldError::new(msg, graphql_value!({"code": "FORBIDDEN"})),
+            Self::Forbidden(msg) => {
+                warn!(request_id, msg, "forbidden");
+                denial_field_error(msg, "FORBIDDEN", request_id)
+            }
             Self::Unauthorized(msg) => {
-                FieldError::new(msg, graphql_value!({"code": "UNAUTHORIZED"}))
+                warn!(request_id, msg, "unauthorized");
+                denial_field_error(msg, "UNAUTHORIZED", request_id)
+            }
+            Self::NotFound(msg) => {
+                warn!(request_id, msg, "not found");
+                denial_field_error(msg, "NOT_FOUND", request_id)
             }
-            Self::NotFound(msg) => FieldError::new(msg, graphql_value!({"code": "NOT_FOUND"})),
             Self::InvalidInput(errors) => from_validation_errors(errors),
             _ => self.into(),
         }
@@ -217,21 +384,69 @@ fn check_claims(ctx: &Context) -> Result<&JWTPayload, CoreError> {
         .ok_or(CoreError::Unauthorized("You're not logged in"))
 }
 
-async fn check_admin(ctx: &Context) -> Result<(), CoreError> {
-    let user = check_user(ctx).await?;
-    if !user.is_admin {
+/// Proof that `check_admin` has succeeded for the current request. Admin-gated resolvers call
+/// `.locator()` on this instead of reading `ctx.locator` directly. `Context`'s `locator` field is
+/// private to this module, so code outside it has to go through one of these three guards.
+/// Inside this module the field stays readable: the compiler does not stop a resolver or helper
+/// here from calling `ctx.locator` without a guard, and several (e.g. `job_run_stats`,
+/// `server_info`, the `diagnostics` probes) do.
+pub struct AdminCtx(UserSecured, Arc<dyn ServiceLocator>);
+
+impl AdminCtx {
+    pub fn user(&self) -> &UserSecured {
+        &self.0
+    }
+
+    pub(crate) fn locator(&self) -> &Arc<dyn ServiceLocator> {
+        &self.1
+    }
+}
+
+/// Proof that the request is an interactive, non-token session belonging to a logged-in user.
+pub struct UserCtx(UserSecured, Arc<dyn ServiceLocator>);
+
+impl UserCtx {
+    pub fn user(&self) -> &UserSecured {
+        &self.0
+    }
+
+    pub(crate) fn locator(&self) -> &Arc<dyn ServiceLocator> {
+        &self.1
+    }
+}
+
+/// Like `UserCtx`, but the claims may have been generated from a personal access token rather
+/// than an interactive session.
+pub struct AuthTokenCtx(UserSecured, Arc<dyn ServiceLocator>);
+
+impl AuthTokenCtx {
+    pub fn user(&self) -> &UserSecured {
+        &self.0
+    }
+
+    pub(crate) fn locator(&self) -> &Arc<dyn ServiceLocator> {
+        &self.1
+    }
+}
+
+async fn check_admin(ctx: &Context) -> Result<AdminCtx, CoreError> {
+    let user_ctx = check_user(ctx).await?;
+    if !user_ctx.user().is_admin {
         return Err(CoreError::Forbidden("You must be admin to proceed"));
     }
 
-    Ok(())
+    let UserCtx(user, locator) = user_ctx;
+    Ok(AdminCtx(user, locator))
 }
 
-async fn check_user(ctx: &Context) -> Result<UserSecured, CoreError> {
-    check_user_and_auth_token(ctx, false).await
+async fn check_user(ctx: &Context) -> Result<UserCtx, CoreError> {
+    let user = check_user_and_auth_token(ctx, false).await?;
+    Ok(UserCtx(user, ctx.locator.clone()))
 }
 
-async fn check_user_allow_auth_token(ctx: &Context) -> Result<UserSecured, CoreError> {
-    check_user_and_auth_token(ctx, true).await
+async fn check_user_allow_auth_token(ctx: &Context) -> Result<AuthTokenCtx, CoreError> {
+    let user = check_user_and_auth_token(ctx, true).await?;
+    Ok(AuthTokenCtx(user, ctx.locator.clone()))
 }
 
 async fn check_user_and_auth_token(
@@ -248,6 +463,144 @@ async fn check_user_and_auth_token(
     Ok(user)
 }
 
+/// Capability carried by a scoped personal access token. A token whose `JWTPayload::scopes` is
+/// `None` predates scoping and keeps the old all-or-nothing behavior.
+#[derive(GraphQLEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scope {
+    RepositoriesRead,
+    ThreadsRead,
+    ThreadsWrite,
+    AdminAll,
+}
+
+fn check_scope(claims: &JWTPayload, scope: Scope) -> Result<(), CoreError> {
+    match &claims.scopes {
+        None => Ok(()),
+        Some(scopes) if scopes.contains(&scope) || scopes.contains(&Scope::AdminAll) => Ok(()),
+        Some(_) => Err(CoreError::Forbidden(
+            "This token does not have the required scope",
+        )),
+    }
+}
+
+/// Like `check_user_allow_auth_token`, but additionally requires `scope` when the claims were
+/// minted for a personal access token rather than an interactive session.
+async fn check_user_with_scope(ctx: &Context, scope: Scope) -> Result<AuthTokenCtx, CoreError> {
+    let auth_ctx = check_user_allow_auth_token(ctx).await?;
+    let claims = check_claims(ctx)?;
+    if claims.is_generated_from_auth_token {
+        check_scope(claims, scope)?;
+    }
+    Ok(auth_ctx)
+}
+
+/// Record a privileged mutation to the audit log. Best-effort: a logging failure must not
+/// fail the mutation that already succeeded, so errors are only traced.
+async fn record_audit_log(
+    ctx: &Context,
+    action: AuditAction,
+    target_id: Option<String>,
+    summary: impl Into<String>,
+) {
+    record_audit_log_with_metadata(ctx, action, target_id, summary, None).await
+}
+
+/// Like [`record_audit_log`], but attaches a structured (JSON text) metadata blob for changes
+/// where a human-readable summary alone loses detail compliance reviews care about.
+async fn record_audit_log_with_metadata(
+    ctx: &Context,
+    action: AuditAction,
+    target_id: Option<String>,
+    summary: impl Into<String>,
+    metadata: Option<String>,
+) {
+    let Some(claims) = ctx.claims.as_ref() else {
+        return;
+    };
+    if let Err(e) = ctx
+        .locator
+        .audit()
+        .record(
+            &ID::from(claims.sub.clone()),
+            action,
+            target_id,
+            summary.into(),
+            metadata,
+            ctx.source_ip.clone(),
+        )
+        .await
+    {
+        error!("Failed to record audit log: {e}");
+    }
+}
+
+/// Fan a completed sensitive mutation out to both of this hook subsystem's built-in consumers:
+/// the audit log (already recorded by every other privileged mutation) and the outbound
+/// webhook dispatcher, for external systems that want to react to events like `delete_page` or
+/// `grant_source_id_read_access`. Takes the mutation's own `result` so a failed mutation is
+/// reported as `MutationOutcome::Error` instead of never reaching a hook at all -- callers run
+/// the fallible call themselves and pass the `Result` through here before propagating it with
+/// `?`. Best-effort, like `record_audit_log`.
+async fn fire_mutation_hook<T>(
+    ctx: &Context,
+    action: AuditAction,
+    operation: &'static str,
+    target_id: Option<String>,
+    summary: impl Into<String>,
+    result: &Result<T>,
+) {
+    let summary = summary.into();
+    match result {
+        Ok(_) => record_audit_log(ctx, action, target_id.clone(), summary).await,
+        Err(e) => {
+            record_audit_log(
+                ctx,
+                action,
+                target_id.clone(),
+                format!("{summary} (failed: {e})"),
+            )
+            .await
+        }
+    }
+
+    let Some(claims) = ctx.claims.as_ref() else {
+        return;
+    };
+    let outcome = match result {
+        Ok(_) => MutationOutcome::Success,
+        Err(e) => MutationOutcome::Error(e.to_string()),
+    };
+    let event = MutationEvent {
+        actor_id: claims.sub.clone(),
+        operation,
+        target_ids: target_id.into_iter().collect(),
+        outcome,
+    };
+    ctx.locator.mutation_hooks().fire(event).await;
+}
+
+/// Notify every connected `page_events(page_id)` subscriber of a successful write, so
+/// collaborators viewing the same page see the change without polling. Best-effort, like
+/// `fire_mutation_hook`: a page with no subscribers just drops the event.
+async fn publish_page_event(
+    ctx: &Context,
+    page_id: &ID,
+    kind: PageEventKind,
+    section_id: Option<ID>,
+) {
+    ctx.locator
+        .page_events()
+        .publish(
+            page_id,
+            PageEvent {
+                page_id: page_id.clone(),
+                kind,
+                section_id,
+            },
+        )
+        .await;
+}
+
 async fn check_license(ctx: &Context, license_type: &[LicenseType]) -> Result<(), CoreError> {
     let license = ctx.locator.license().read().await?;
 
@@ -260,6 +613,40 @@ async fn check_license(ctx: &Context, license_type: &[LicenseType]) -> Result<()
     license.ensure_valid_license()
 }
 
+/// Ask the pluggable [`PolicyEngine`] to decide `action`. This is the whole authorization
+/// decision for the actions routed through it (page updates via `authorize_update_page`, group
+/// membership changes, source read grants and revokes), in place of a compiled-in
+/// `check_admin`/`check_update_page` call, so operators can enforce attribute-based rules (e.g.
+/// which group may grant read access to which source) without a Tabby release.
+async fn check_policy(
+    ctx: &Context,
+    action: PolicyAction,
+    resource: PolicyResource,
+) -> Result<(), CoreError> {
+    let claims = ctx
+        .claims
+        .as_ref()
+        .ok_or(CoreError::Unauthorized("You must be logged in for this operation"))?;
+
+    let subject = PolicySubject {
+        user_id: claims.sub.clone(),
+        group_ids: claims.groups.clone(),
+    };
+
+    match ctx.locator.policy_engine().evaluate(subject, action, resource).await? {
+        PolicyDecision::Allow => Ok(()),
+        PolicyDecision::Deny => Err(CoreError::Forbidden(
+            "You are not authorized to perform this operation",
+        )),
+    }
+}
+
+fn require_page_service(ctx: &Context) -> Result<Arc<dyn PageService>> {
+    ctx.locator
+        .page()
+        .ok_or(CoreError::Forbidden("Page service is not enabled"))
+}
+
 #[derive(GraphQLEnum)]
 enum ModelHealthBackend {
     Chat,
@@ -273,6 +660,250 @@ struct ModelBackendHealthInfo {
     latency_ms: i32,
 }
 
+/// Timeout applied to each individual probe run by the `diagnostics` query, so a single
+/// unresponsive subsystem doesn't hang the whole report.
+const DIAGNOSTIC_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(GraphQLEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum DiagnosticStatus {
+    Ok,
+    Degraded,
+    Failed,
+}
+
+#[derive(GraphQLObject, Clone, Debug)]
+struct DiagnosticCheck {
+    status: DiagnosticStatus,
+    message: Option<String>,
+}
+
+impl DiagnosticCheck {
+    fn ok() -> Self {
+        Self {
+            status: DiagnosticStatus::Ok,
+            message: None,
+        }
+    }
+
+    fn degraded(message: impl Into<String>) -> Self {
+        Self {
+            status: DiagnosticStatus::Degraded,
+            message: Some(message.into()),
+        }
+    }
+
+    fn failed(message: impl Into<String>) -> Self {
+        Self {
+            status: DiagnosticStatus::Failed,
+            message: Some(message.into()),
+        }
+    }
+
+    fn timed_out() -> Self {
+        Self::failed("Timed out while running the diagnostic probe")
+    }
+}
+
+#[derive(GraphQLObject, Clone, Debug)]
+struct ModelBackendDiagnostic {
+    status: DiagnosticStatus,
+    message: Option<String>,
+    health: Option<ModelBackendHealthInfo>,
+}
+
+/// Aggregated, self-test style snapshot of every subsystem `Query::server_info` can't see into.
+///
+/// `database` is reachability only, not a schema/migration version check, and `license` is
+/// validity only, not seat usage against the license's allowed count -- see the doc comments on
+/// [`probe_database`] and [`probe_license`].
+#[derive(GraphQLObject, Debug)]
+struct Diagnostics {
+    smtp: DiagnosticCheck,
+    database: DiagnosticCheck,
+    disk_usage: DiskUsageStats,
+    chat: ModelBackendDiagnostic,
+    completion: ModelBackendDiagnostic,
+    embedding: ModelBackendDiagnostic,
+    integrations: DiagnosticCheck,
+    license: DiagnosticCheck,
+}
+
+async fn probe_smtp(ctx: &Context) -> DiagnosticCheck {
+    match ctx.locator.email().read_setting().await {
+        Ok(None) => DiagnosticCheck::degraded("SMTP is not configured"),
+        Ok(Some(_)) => {
+            match timeout(
+                DIAGNOSTIC_PROBE_TIMEOUT,
+                ctx.locator.email().test_connection(),
+            )
+            .await
+            {
+                Ok(Ok(())) => DiagnosticCheck::ok(),
+                Ok(Err(e)) => DiagnosticCheck::failed(e.to_string()),
+                Err(_) => DiagnosticCheck::timed_out(),
+            }
+        }
+        Err(e) => DiagnosticCheck::failed(e.to_string()),
+    }
+}
+
+/// Reachability only: `is_admin_initialized` issues a real query, making it a cheap probe that
+/// the connection pool and the underlying database are both up. This deliberately does not check
+/// the applied schema/migration version -- `tabby-schema` has no migration-tracking mechanism to
+/// query, and exposing one would mean threading a migrations handle (owned by whatever crate runs
+/// them) into a `ServiceLocator` that has no other reason to hold one.
+async fn probe_database(ctx: &Context) -> DiagnosticCheck {
+    match timeout(
+        DIAGNOSTIC_PROBE_TIMEOUT,
+        ctx.locator.auth().is_admin_initialized(),
+    )
+    .await
+    {
+        Ok(Ok(_)) => DiagnosticCheck::ok(),
+        Ok(Err(e)) => DiagnosticCheck::failed(e.to_string()),
+        Err(_) => DiagnosticCheck::timed_out(),
+    }
+}
+
+async fn probe_integrations(ctx: &Context) -> DiagnosticCheck {
+    let integrations = timeout(
+        DIAGNOSTIC_PROBE_TIMEOUT,
+        ctx.locator
+            .integration()
+            .list_integrations(None, None, None, None, None, None),
+    )
+    .await;
+
+    match integrations {
+        Ok(Ok(connection)) => {
+            let unreachable = connection
+                .edges
+                .iter()
+                .filter(|edge| edge.node.message.is_some())
+                .count();
+            if unreachable == 0 {
+                DiagnosticCheck::ok()
+            } else {
+                DiagnosticCheck::degraded(format!(
+                    "{unreachable} integration(s) configured but unreachable"
+                ))
+            }
+        }
+        Ok(Err(e)) => DiagnosticCheck::failed(e.to_string()),
+        Err(_) => DiagnosticCheck::timed_out(),
+    }
+}
+
+/// Validity only: `ensure_valid_license` rejects an expired, unissued, or otherwise invalid
+/// license. Seat usage is not compared against the license's allowed seat count.
+async fn probe_license(ctx: &Context) -> DiagnosticCheck {
+    match timeout(DIAGNOSTIC_PROBE_TIMEOUT, ctx.locator.license().read()).await {
+        Ok(Ok(license)) => match license.ensure_valid_license() {
+            Ok(()) => DiagnosticCheck::ok(),
+            Err(e) => DiagnosticCheck::degraded(e.to_string()),
+        },
+        Ok(Err(e)) => DiagnosticCheck::failed(e.to_string()),
+        Err(_) => DiagnosticCheck::timed_out(),
+    }
+}
+
+async fn probe_model_backend(
+    probe: impl std::future::Future<Output = std::result::Result<i32, String>>,
+) -> ModelBackendDiagnostic {
+    match timeout(DIAGNOSTIC_PROBE_TIMEOUT, probe).await {
+        Ok(Ok(latency_ms)) => ModelBackendDiagnostic {
+            status: DiagnosticStatus::Ok,
+            message: None,
+            health: Some(ModelBackendHealthInfo { latency_ms }),
+        },
+        Ok(Err(message)) => ModelBackendDiagnostic {
+            status: DiagnosticStatus::Failed,
+            message: Some(message),
+            health: None,
+        },
+        Err(_) => ModelBackendDiagnostic {
+            status: DiagnosticStatus::Failed,
+            message: Some("Timed out while running the diagnostic probe".into()),
+            health: None,
+        },
+    }
+}
+
+async fn diagnose_chat(ctx: &Context) -> ModelBackendDiagnostic {
+    let Some(chat) = ctx.locator.chat() else {
+        return ModelBackendDiagnostic {
+            status: DiagnosticStatus::Degraded,
+            message: Some("Chat model backend is not enabled".into()),
+            health: None,
+        };
+    };
+
+    let probe = async move {
+        let request = CreateChatCompletionRequestArgs::default()
+            .messages(vec![ChatCompletionRequestMessage::User(
+                ChatCompletionRequestUserMessageArgs::default()
+                    .content("Hello, please reply in short")
+                    .build()
+                    .expect("Failed to build chat completion message"),
+            )])
+            .build()
+            .expect("Failed to build chat completion request");
+        let start = Instant::now();
+        chat.chat(request)
+            .await
+            .map(|_| start.elapsed().as_millis() as i32)
+            .map_err(|e| e.to_string())
+    };
+    probe_model_backend(probe).await
+}
+
+async fn diagnose_completion(ctx: &Context) -> ModelBackendDiagnostic {
+    let Some(completion) = ctx.locator.completion() else {
+        return ModelBackendDiagnostic {
+            status: DiagnosticStatus::Degraded,
+            message: Some("Completion model backend is not enabled".into()),
+            health: None,
+        };
+    };
+
+    let probe = async move {
+        let config = CompletionConfig::default();
+        let options = CompletionOptionsBuilder::default()
+            .max_decoding_tokens(config.max_decoding_tokens as i32)
+            .sampling_temperature(0.1)
+            .seed(0)
+            .build()
+            .expect("Failed to build completion options");
+
+        let start = Instant::now();
+        let (first, _) = completion
+            .generate("def fib(n):\n", options)
+            .await
+            .into_future()
+            .await;
+
+        if first.is_some() {
+            Ok(start.elapsed().as_millis() as i32)
+        } else {
+            Err("Failed to connect to the completion model".to_string())
+        }
+    };
+    probe_model_backend(probe).await
+}
+
+async fn diagnose_embedding(ctx: &Context) -> ModelBackendDiagnostic {
+    let embedding = ctx.locator.embedding();
+    let probe = async move {
+        let start = Instant::now();
+        embedding
+            .embed("hello Tabby")
+            .await
+            .map(|_| start.elapsed().as_millis() as i32)
+            .map_err(|e| e.to_string())
+    };
+    probe_model_backend(probe).await
+}
+
 #[derive(GraphQLObject, Clone, Debug)]
 pub struct ChatCompletionMessage {
     pub role: String,
@@ -321,12 +952,32 @@ pub struct Query;
 #[graphql_object(context = Context)]
 impl Query {
     async fn registration_token(ctx: &Context) -> Result<String> {
-        check_admin(ctx).await?;
-        ctx.locator.worker().read_registration_token().await
+        instrumented(ctx, "registration_token", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().worker().read_registration_token().await
+        })
+        .await
     }
 
     async fn me(ctx: &Context) -> Result<UserSecured> {
-        check_user_allow_auth_token(ctx).await
+        instrumented(ctx, "me", async move {
+            check_user_allow_auth_token(ctx)
+                .await
+                .map(|guard| guard.user().clone())
+        })
+        .await
+    }
+
+    /// List the personal access tokens owned by the current user.
+    async fn personal_access_tokens(ctx: &Context) -> Result<Vec<PersonalAccessToken>> {
+        instrumented(ctx, "personal_access_tokens", async move {
+            let claims = check_claims(ctx)?;
+            ctx.locator
+                .auth()
+                .list_personal_access_tokens(&claims.sub)
+                .await
+        })
+        .await
     }
 
     /// List users, accessible for all login users.
@@ -338,20 +989,23 @@ impl Query {
         first: Option<i32>,
         last: Option<i32>,
     ) -> Result<Connection<UserValue>> {
-        check_user(ctx).await?;
-        relay::query_async(
-            after,
-            before,
-            first,
-            last,
-            |after, before, first, last| async move {
-                ctx.locator
-                    .auth()
-                    .list_users(ids, after, before, first, last)
-                    .await
-                    .map(|users| users.into_iter().map(UserValue::UserSecured).collect())
-            },
-        )
+        instrumented(ctx, "users", async move {
+            let UserCtx(_, locator) = check_user(ctx).await?;
+            relay::query_async(
+                after,
+                before,
+                first,
+                last,
+                |after, before, first, last| async move {
+                    locator
+                        .auth()
+                        .list_users(ids, after, before, first, last)
+                        .await
+                        .map(|users| users.into_iter().map(UserValue::UserSecured).collect())
+                },
+            )
+            .await
+        })
         .await
     }
 
@@ -362,19 +1016,22 @@ impl Query {
         first: Option<i32>,
         last: Option<i32>,
     ) -> Result<Connection<Invitation>> {
-        check_admin(ctx).await?;
-        relay::query_async(
-            after,
-            before,
-            first,
-            last,
-            |after, before, first, last| async move {
-                ctx.locator
-                    .auth()
-                    .list_invitations(after, before, first, last)
-                    .await
-            },
-        )
+        instrumented(ctx, "invitations", async move {
+            let admin = check_admin(ctx).await?;
+            relay::query_async(
+                after,
+                before,
+                first,
+                last,
+                |after, before, first, last| async move {
+                    admin.locator()
+                        .auth()
+                        .list_invitations(after, before, first, last)
+                        .await
+                },
+            )
+            .await
+        })
         .await
     }
 
@@ -387,39 +1044,71 @@ impl Query {
         first: Option<i32>,
         last: Option<i32>,
     ) -> Result<Connection<JobRun>> {
-        check_admin(ctx).await?;
-        relay::query_async(
-            after,
-            before,
-            first,
-            last,
-            |after, before, first, last| async move {
-                ctx.locator
-                    .job()
-                    .list(ids, jobs, after, before, first, last)
-                    .await
-            },
-        )
+        instrumented(ctx, "job_runs", async move {
+            let admin = check_admin(ctx).await?;
+            relay::query_async(
+                after,
+                before,
+                first,
+                last,
+                |after, before, first, last| async move {
+                    admin.locator()
+                        .job()
+                        .list(ids, jobs, after, before, first, last)
+                        .await
+                },
+            )
+            .await
+        })
         .await
     }
 
     async fn job_run_stats(ctx: &Context, jobs: Option<Vec<String>>) -> Result<JobStats> {
-        ctx.locator.job().compute_stats(jobs).await
+        instrumented(ctx, "job_run_stats", async move {
+            ctx.locator.job().compute_stats(jobs).await
+        })
+        .await
     }
 
     async fn email_setting(ctx: &Context) -> Result<Option<EmailSetting>> {
-        check_admin(ctx).await?;
-        ctx.locator.email().read_setting().await
+        instrumented(ctx, "email_setting", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().email().read_setting().await
+        })
+        .await
     }
 
     async fn network_setting(ctx: &Context) -> Result<NetworkSetting> {
-        check_admin(ctx).await?;
-        ctx.locator.setting().read_network_setting().await
+        instrumented(ctx, "network_setting", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().setting().read_network_setting().await
+        })
+        .await
+    }
+
+    async fn captcha_setting(ctx: &Context) -> Result<Option<CaptchaSetting>> {
+        instrumented(ctx, "captcha_setting", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().captcha().read_setting().await
+        })
+        .await
+    }
+
+    /// Request a new CAPTCHA challenge to present before `register`. Unauthenticated by design:
+    /// this is the bot check that gates signup itself.
+    async fn get_captcha(ctx: &Context) -> Result<CaptchaChallenge> {
+        instrumented(ctx, "get_captcha", async move {
+            ctx.locator.captcha().generate_challenge().await
+        })
+        .await
     }
 
     async fn security_setting(ctx: &Context) -> Result<SecuritySetting> {
-        check_admin(ctx).await?;
-        ctx.locator.setting().read_security_setting().await
+        instrumented(ctx, "security_setting", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().setting().read_security_setting().await
+        })
+        .await
     }
 
     async fn git_repositories(
@@ -430,20 +1119,23 @@ impl Query {
         first: Option<i32>,
         last: Option<i32>,
     ) -> Result<Connection<repository::GitRepository>> {
-        check_admin(ctx).await?;
-        relay::query_async(
-            after,
-            before,
-            first,
-            last,
-            |after, before, first, last| async move {
-                ctx.locator
-                    .repository()
-                    .git()
-                    .list(after, before, first, last)
-                    .await
-            },
-        )
+        instrumented(ctx, "git_repositories", async move {
+            let admin = check_admin(ctx).await?;
+            relay::query_async(
+                after,
+                before,
+                first,
+                last,
+                |after, before, first, last| async move {
+                    admin.locator()
+                        .repository()
+                        .git()
+                        .list(after, before, first, last)
+                        .await
+                },
+            )
+            .await
+        })
         .await
     }
 
@@ -455,11 +1147,14 @@ impl Query {
         rev: Option<String>,
         pattern: String,
     ) -> Result<Vec<FileEntrySearchResult>> {
-        let user = check_user(ctx).await?;
-        ctx.locator
-            .repository()
-            .search_files(&user.policy, &kind, &id, rev.as_deref(), &pattern, 40)
-            .await
+        instrumented(ctx, "repository_search", async move {
+            let UserCtx(user, locator) = check_user(ctx).await?;
+            locator
+                .repository()
+                .search_files(&user.policy, &kind, &id, rev.as_deref(), &pattern, 40)
+                .await
+        })
+        .await
     }
 
     /// File content search with a grep-like experience.
@@ -481,83 +1176,137 @@ impl Query {
         rev: Option<String>,
         query: String,
     ) -> Result<RepositoryGrepOutput> {
-        let user = check_user(ctx).await?;
-
-        let start_time = chrono::offset::Utc::now();
-        let files = ctx
-            .locator
-            .repository()
-            .grep(&user.policy, &kind, &id, rev.as_deref(), &query, 40)
-            .await?;
-        let end_time = chrono::offset::Utc::now();
-        let elapsed_ms = (end_time - start_time).num_milliseconds() as i32;
-        Ok(RepositoryGrepOutput { files, elapsed_ms })
+        instrumented(ctx, "repository_grep", async move {
+            let UserCtx(user, locator) = check_user(ctx).await?;
+
+            let start_time = chrono::offset::Utc::now();
+            let files = locator
+                .repository()
+                .grep(&user.policy, &kind, &id, rev.as_deref(), &query, 40)
+                .await?;
+            let end_time = chrono::offset::Utc::now();
+            let elapsed_ms = (end_time - start_time).num_milliseconds() as i32;
+            Ok(RepositoryGrepOutput { files, elapsed_ms })
+        })
+        .await
     }
 
     async fn auth_providers(ctx: &Context) -> Result<Vec<AuthProvider>> {
-        let mut providers = vec![];
+        instrumented(ctx, "auth_providers", async move {
+            let mut providers = vec![];
+
+            let auth = ctx.locator.auth();
+            for x in OAuthProvider::iter() {
+                if auth
+                    .read_oauth_credential(x.clone())
+                    .await
+                    .is_ok_and(|x| x.is_some())
+                {
+                    providers.push(x.into());
+                }
+            }
+
+            if auth.read_ldap_credential().await.is_ok_and(|x| x.is_some()) {
+                providers.push(AuthProvider {
+                    kind: AuthProviderKind::Ldap,
+                });
+            }
 
-        let auth = ctx.locator.auth();
-        for x in OAuthProvider::iter() {
-            if auth
-                .read_oauth_credential(x.clone())
+            if ctx
+                .locator
+                .oidc()
+                .read_credential()
                 .await
                 .is_ok_and(|x| x.is_some())
             {
-                providers.push(x.into());
+                providers.push(AuthProvider {
+                    kind: AuthProviderKind::Oidc,
+                });
             }
-        }
-
-        if auth.read_ldap_credential().await.is_ok_and(|x| x.is_some()) {
-            providers.push(AuthProvider {
-                kind: AuthProviderKind::Ldap,
-            });
-        }
 
-        Ok(providers)
+            Ok(providers)
+        })
+        .await
     }
 
     async fn oauth_credential(
         ctx: &Context,
         provider: OAuthProvider,
     ) -> Result<Option<OAuthCredential>> {
-        check_admin(ctx).await?;
-        ctx.locator.auth().read_oauth_credential(provider).await
+        instrumented(ctx, "oauth_credential", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().auth().read_oauth_credential(provider).await
+        })
+        .await
     }
 
     async fn oauth_callback_url(ctx: &Context, provider: OAuthProvider) -> Result<String> {
-        check_admin(ctx).await?;
-        ctx.locator.auth().oauth_callback_url(provider).await
+        instrumented(ctx, "oauth_callback_url", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().auth().oauth_callback_url(provider).await
+        })
+        .await
     }
 
     async fn ldap_credential(ctx: &Context) -> Result<Option<LdapCredential>> {
-        check_admin(ctx).await?;
-        ctx.locator.auth().read_ldap_credential().await
+        instrumented(ctx, "ldap_credential", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().auth().read_ldap_credential().await
+        })
+        .await
     }
 
-    async fn server_info(ctx: &Context) -> Result<ServerInfo> {
-        Ok(ServerInfo {
-            is_admin_initialized: ctx.locator.auth().is_admin_initialized().await?,
-            is_chat_enabled: ctx.locator.worker().is_chat_enabled().await?,
-            is_email_configured: ctx.locator.email().read_setting().await?.is_some(),
-            allow_self_signup: ctx.locator.auth().allow_self_signup().await?,
-            disable_password_login: ctx
-                .locator
-                .setting()
-                .read_security_setting()
-                .await?
-                .disable_password_login,
-            is_demo_mode: env::is_demo_mode(),
+    async fn oidc_credential(ctx: &Context) -> Result<Option<OidcCredential>> {
+        instrumented(ctx, "oidc_credential", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().oidc().read_credential().await
         })
+        .await
     }
 
-    async fn license(ctx: &Context) -> Result<LicenseInfo> {
-        ctx.locator.license().read().await
+    async fn oidc_callback_url(ctx: &Context) -> Result<String> {
+        instrumented(ctx, "oidc_callback_url", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().oidc().callback_url().await
+        })
+        .await
     }
 
-    // FIXME(meng): This is a temporary solution to expose the list of jobs, we should consider switching to a enum based approach.
-    async fn jobs() -> Result<Vec<String>> {
-        Ok(
+    async fn server_info(ctx: &Context) -> Result<ServerInfo> {
+        instrumented(ctx, "server_info", async move {
+            Ok(ServerInfo {
+                is_admin_initialized: ctx.locator.auth().is_admin_initialized().await?,
+                is_chat_enabled: ctx.locator.worker().is_chat_enabled().await?,
+                is_email_configured: ctx.locator.email().read_setting().await?.is_some(),
+                allow_self_signup: ctx.locator.auth().allow_self_signup().await?,
+                disable_password_login: ctx
+                    .locator
+                    .setting()
+                    .read_security_setting()
+                    .await?
+                    .disable_password_login,
+                is_demo_mode: env::is_demo_mode(),
+                require_captcha: ctx
+                    .locator
+                    .captcha()
+                    .read_setting()
+                    .await?
+                    .is_some_and(|setting| setting.require_captcha),
+            })
+        })
+        .await
+    }
+
+    async fn license(ctx: &Context) -> Result<LicenseInfo> {
+        instrumented(ctx, "license", async move {
+            ctx.locator.license().read().await
+        })
+        .await
+    }
+
+    // FIXME(meng): This is a temporary solution to expose the list of jobs, we should consider switching to a enum based approach.
+    async fn jobs() -> Result<Vec<String>> {
+        Ok(
             vec!["scheduler_git", "scheduler_github_gitlab", "web_crawler"]
                 .into_iter()
                 .map(Into::into)
@@ -569,10 +1318,13 @@ impl Query {
         ctx: &Context,
         users: Option<Vec<ID>>,
     ) -> Result<Vec<CompletionStats>> {
-        let users = users.unwrap_or_default();
-        let user = check_user(ctx).await?;
-        user.policy.check_read_analytic(&users)?;
-        ctx.locator.analytic().daily_stats_in_past_year(users).await
+        instrumented(ctx, "daily_stats_in_past_year", async move {
+            let users = users.unwrap_or_default();
+            let UserCtx(user, locator) = check_user(ctx).await?;
+            user.policy.check_read_analytic(&users)?;
+            locator.analytic().daily_stats_in_past_year(users).await
+        })
+        .await
     }
 
     async fn daily_stats(
@@ -582,26 +1334,32 @@ impl Query {
         users: Option<Vec<ID>>,
         languages: Option<Vec<analytic::Language>>,
     ) -> Result<Vec<CompletionStats>> {
-        let users = users.unwrap_or_default();
-        let user = check_user(ctx).await?;
-        user.policy.check_read_analytic(&users)?;
-        ctx.locator
-            .analytic()
-            .daily_stats(start, end, users, languages.unwrap_or_default())
-            .await
+        instrumented(ctx, "daily_stats", async move {
+            let users = users.unwrap_or_default();
+            let UserCtx(user, locator) = check_user(ctx).await?;
+            user.policy.check_read_analytic(&users)?;
+            locator
+                .analytic()
+                .daily_stats(start, end, users, languages.unwrap_or_default())
+                .await
+        })
+        .await
     }
 
     async fn chat_daily_stats_in_past_year(
         ctx: &Context,
         users: Option<Vec<ID>>,
     ) -> Result<Vec<ChatCompletionStats>> {
-        let users = users.unwrap_or_default();
-        let user = check_user(ctx).await?;
-        user.policy.check_read_analytic(&users)?;
-        ctx.locator
-            .analytic()
-            .chat_daily_stats_in_past_year(users)
-            .await
+        instrumented(ctx, "chat_daily_stats_in_past_year", async move {
+            let users = users.unwrap_or_default();
+            let UserCtx(user, locator) = check_user(ctx).await?;
+            user.policy.check_read_analytic(&users)?;
+            locator
+                .analytic()
+                .chat_daily_stats_in_past_year(users)
+                .await
+        })
+        .await
     }
 
     async fn chat_daily_stats(
@@ -610,13 +1368,16 @@ impl Query {
         end: DateTime<Utc>,
         users: Option<Vec<ID>>,
     ) -> Result<Vec<ChatCompletionStats>> {
-        let users = users.unwrap_or_default();
-        let user = check_user(ctx).await?;
-        user.policy.check_read_analytic(&users)?;
-        ctx.locator
-            .analytic()
-            .chat_daily_stats(start, end, users)
-            .await
+        instrumented(ctx, "chat_daily_stats", async move {
+            let users = users.unwrap_or_default();
+            let UserCtx(user, locator) = check_user(ctx).await?;
+            user.policy.check_read_analytic(&users)?;
+            locator
+                .analytic()
+                .chat_daily_stats(start, end, users)
+                .await
+        })
+        .await
     }
 
     async fn user_events(
@@ -633,52 +1394,118 @@ impl Query {
         start: DateTime<Utc>,
         end: DateTime<Utc>,
     ) -> Result<Connection<UserEvent>> {
-        check_admin(ctx).await?;
-        relay::query_async(
-            after,
-            before,
-            first,
-            last,
-            |after, before, first, last| async move {
-                ctx.locator
-                    .user_event()
-                    .list(
-                        after,
-                        before,
-                        first,
-                        last,
-                        users.unwrap_or_default(),
-                        start,
-                        end,
-                    )
-                    .await
-            },
-        )
+        instrumented(ctx, "user_events", async move {
+            let admin = check_admin(ctx).await?;
+            relay::query_async(
+                after,
+                before,
+                first,
+                last,
+                |after, before, first, last| async move {
+                    admin.locator()
+                        .user_event()
+                        .list(
+                            after,
+                            before,
+                            first,
+                            last,
+                            users.unwrap_or_default(),
+                            start,
+                            end,
+                        )
+                        .await
+                },
+            )
+            .await
+        })
+        .await
+    }
+
+    /// List recorded privileged mutations, newest first.
+    async fn audit_logs(
+        ctx: &Context,
+
+        // pagination arguments
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+
+        // filter arguments
+        actors: Option<Vec<ID>>,
+        actions: Option<Vec<AuditAction>>,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<Connection<AuditLog>> {
+        instrumented(ctx, "audit_logs", async move {
+            let admin = check_admin(ctx).await?;
+            relay::query_async(
+                after,
+                before,
+                first,
+                last,
+                |after, before, first, last| async move {
+                    admin.locator()
+                        .audit()
+                        .list(actors, actions, start, end, after, before, first, last)
+                        .await
+                },
+            )
+            .await
+        })
+        .await
+    }
+
+    async fn list_backups(ctx: &Context) -> Result<Vec<BackupArchive>> {
+        instrumented(ctx, "list_backups", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().backup().list().await
+        })
+        .await
+    }
+
+    async fn backup_download_url(ctx: &Context, id: ID) -> Result<String> {
+        instrumented(ctx, "backup_download_url", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().backup().download_url(&id).await
+        })
         .await
     }
 
     async fn notifications(ctx: &Context) -> Result<Vec<notification::Notification>> {
-        let user = check_user(ctx).await?;
-        ctx.locator.notification().list(&user.id).await
+        instrumented(ctx, "notifications", async move {
+            let UserCtx(user, locator) = check_user(ctx).await?;
+            locator.notification().list(&user.id).await
+        })
+        .await
     }
 
     async fn disk_usage_stats(ctx: &Context) -> Result<DiskUsageStats> {
-        check_admin(ctx).await?;
-        ctx.locator.analytic().disk_usage_stats().await
+        instrumented(ctx, "disk_usage_stats", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().analytic().disk_usage_stats().await
+        })
+        .await
     }
 
     async fn repository_list(ctx: &Context) -> Result<Vec<Repository>> {
-        let user = check_user_allow_auth_token(ctx).await?;
+        instrumented(ctx, "repository_list", async move {
+            let AuthTokenCtx(user, locator) = check_user_with_scope(ctx, Scope::RepositoriesRead).await?;
 
-        ctx.locator
-            .repository()
-            .repository_list(Some(&user.policy))
-            .await
+            locator
+                .repository()
+                .repository_list(Some(&user.policy))
+                .await
+        })
+        .await
     }
 
     async fn context_info(ctx: &Context) -> Result<ContextInfo> {
-        let user = check_user_allow_auth_token(ctx).await?;
-        ctx.locator.context().read(Some(&user.policy)).await
+        instrumented(ctx, "context_info", async move {
+            let AuthTokenCtx(user, locator) = check_user_with_scope(ctx, Scope::RepositoriesRead).await?;
+            locator.context().read(Some(&user.policy)).await
+        })
+        .await
     }
 
     async fn integrations(
@@ -690,19 +1517,22 @@ impl Query {
         first: Option<i32>,
         last: Option<i32>,
     ) -> Result<Connection<Integration>> {
-        check_admin(ctx).await?;
-        query_async(
-            after,
-            before,
-            first,
-            last,
-            |after, before, first, last| async move {
-                ctx.locator
-                    .integration()
-                    .list_integrations(ids, kind, after, before, first, last)
-                    .await
-            },
-        )
+        instrumented(ctx, "integrations", async move {
+            let admin = check_admin(ctx).await?;
+            query_async(
+                after,
+                before,
+                first,
+                last,
+                |after, before, first, last| async move {
+                    admin.locator()
+                        .integration()
+                        .list_integrations(ids, kind, after, before, first, last)
+                        .await
+                },
+            )
+            .await
+        })
         .await
     }
 
@@ -716,20 +1546,23 @@ impl Query {
         first: Option<i32>,
         last: Option<i32>,
     ) -> Result<Connection<ProvidedRepository>> {
-        check_admin(ctx).await?;
-        query_async(
-            after,
-            before,
-            first,
-            last,
-            |after, before, first, last| async move {
-                ctx.locator
-                    .repository()
-                    .third_party()
-                    .list_repositories_with_filter(ids, kind, active, after, before, first, last)
-                    .await
-            },
-        )
+        instrumented(ctx, "integrated_repositories", async move {
+            let admin = check_admin(ctx).await?;
+            query_async(
+                after,
+                before,
+                first,
+                last,
+                |after, before, first, last| async move {
+                    admin.locator()
+                        .repository()
+                        .third_party()
+                        .list_repositories_with_filter(ids, kind, active, after, before, first, last)
+                        .await
+                },
+            )
+            .await
+        })
         .await
     }
 
@@ -737,8 +1570,11 @@ impl Query {
         ctx: &Context,
         sources: Option<Vec<String>>,
     ) -> Result<Vec<IngestionStats>> {
-        check_admin(ctx).await?;
-        ctx.locator.ingestion().stats(sources).await
+        instrumented(ctx, "ingestion_status", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().ingestion().stats(sources).await
+        })
+        .await
     }
 
     async fn threads(
@@ -750,29 +1586,32 @@ impl Query {
         first: Option<i32>,
         last: Option<i32>,
     ) -> Result<Connection<thread::Thread>> {
-        let user = check_user_allow_auth_token(ctx).await?;
-
-        let threads = relay::query_async(
-            after,
-            before,
-            first,
-            last,
-            |after, before, first, last| async move {
-                ctx.locator
-                    .thread()
-                    .list(ids.as_deref(), is_ephemeral, after, before, first, last)
-                    .await
-            },
-        )
-        .await?;
+        instrumented(ctx, "threads", async move {
+            let AuthTokenCtx(user, locator) = check_user_with_scope(ctx, Scope::ThreadsRead).await?;
+
+            let threads = relay::query_async(
+                after,
+                before,
+                first,
+                last,
+                |after, before, first, last| async move {
+                    locator
+                        .thread()
+                        .list(ids.as_deref(), is_ephemeral, after, before, first, last)
+                        .await
+                },
+            )
+            .await?;
 
-        for thread in threads.edges.iter() {
-            let thread = &thread.node;
-            user.policy
-                .check_read_thread(&thread.user_id, thread.is_ephemeral)?;
-        }
+            for thread in threads.edges.iter() {
+                let thread = &thread.node;
+                user.policy
+                    .check_read_thread(&thread.user_id, thread.is_ephemeral)?;
+            }
 
-        Ok(threads)
+            Ok(threads)
+        })
+        .await
     }
 
     async fn my_threads(
@@ -782,19 +1621,22 @@ impl Query {
         first: Option<i32>,
         last: Option<i32>,
     ) -> Result<Connection<thread::Thread>> {
-        let user = check_user_allow_auth_token(ctx).await?;
-        relay::query_async(
-            after,
-            before,
-            first,
-            last,
-            |after, before, first, last| async move {
-                ctx.locator
-                    .thread()
-                    .list_owned(&user.id, after, before, first, last)
-                    .await
-            },
-        )
+        instrumented(ctx, "my_threads", async move {
+            let AuthTokenCtx(user, locator) = check_user_with_scope(ctx, Scope::ThreadsRead).await?;
+            relay::query_async(
+                after,
+                before,
+                first,
+                last,
+                |after, before, first, last| async move {
+                    locator
+                        .thread()
+                        .list_owned(&user.id, after, before, first, last)
+                        .await
+                },
+            )
+            .await
+        })
         .await
     }
 
@@ -809,29 +1651,31 @@ impl Query {
         first: Option<i32>,
         last: Option<i32>,
     ) -> Result<Connection<thread::Message>> {
-        let user = check_user_allow_auth_token(ctx).await?;
-
-        let thread = ctx
-            .locator
-            .thread()
-            .get(&thread_id)
-            .await?
-            .ok_or_else(|| CoreError::NotFound("thread not found"))?;
-        user.policy
-            .check_read_thread(&thread.user_id, thread.is_ephemeral)?;
-
-        relay::query_async(
-            after,
-            before,
-            first,
-            last,
-            |after, before, first, last| async move {
-                ctx.locator
-                    .thread()
-                    .list_thread_messages(&thread_id, after, before, first, last)
-                    .await
-            },
-        )
+        instrumented(ctx, "thread_messages", async move {
+            let AuthTokenCtx(user, locator) = check_user_with_scope(ctx, Scope::ThreadsRead).await?;
+
+            let thread = locator
+                .thread()
+                .get(&thread_id)
+                .await?
+                .ok_or_else(|| CoreError::NotFound("thread not found"))?;
+            user.policy
+                .check_read_thread(&thread.user_id, thread.is_ephemeral)?;
+
+            relay::query_async(
+                after,
+                before,
+                first,
+                last,
+                |after, before, first, last| async move {
+                    locator
+                        .thread()
+                        .list_thread_messages(&thread_id, after, before, first, last)
+                        .await
+                },
+            )
+            .await
+        })
         .await
     }
 
@@ -844,25 +1688,28 @@ impl Query {
         first: Option<i32>,
         last: Option<i32>,
     ) -> Result<Connection<page::Page>> {
-        check_user(ctx).await?;
-
-        let page_service = if let Some(service) = ctx.locator.page() {
-            service
-        } else {
-            return Err(CoreError::Forbidden("Page service is not enabled"));
-        };
-
-        relay::query_async(
-            after,
-            before,
-            first,
-            last,
-            |after, before, first, last| async move {
-                page_service
-                    .list(ids.as_deref(), after, before, first, last)
-                    .await
-            },
-        )
+        instrumented(ctx, "pages", async move {
+            let UserCtx(_, locator) = check_user(ctx).await?;
+
+            let page_service = if let Some(service) = locator.page() {
+                service
+            } else {
+                return Err(CoreError::Forbidden("Page service is not enabled"));
+            };
+
+            relay::query_async(
+                after,
+                before,
+                first,
+                last,
+                |after, before, first, last| async move {
+                    page_service
+                        .list(ids.as_deref(), after, before, first, last)
+                        .await
+                },
+            )
+            .await
+        })
         .await
     }
 
@@ -874,25 +1721,28 @@ impl Query {
         first: Option<i32>,
         last: Option<i32>,
     ) -> Result<Connection<page::PageSection>> {
-        check_user(ctx).await?;
-
-        let page_service = if let Some(service) = ctx.locator.page() {
-            service
-        } else {
-            return Err(CoreError::Forbidden("Page service is not enabled"));
-        };
-
-        relay::query_async(
-            after,
-            before,
-            first,
-            last,
-            |after, before, first, last| async move {
-                page_service
-                    .list_sections(&page_id, after, before, first, last)
-                    .await
-            },
-        )
+        instrumented(ctx, "page_sections", async move {
+            let UserCtx(_, locator) = check_user(ctx).await?;
+
+            let page_service = if let Some(service) = locator.page() {
+                service
+            } else {
+                return Err(CoreError::Forbidden("Page service is not enabled"));
+            };
+
+            relay::query_async(
+                after,
+                before,
+                first,
+                last,
+                |after, before, first, last| async move {
+                    page_service
+                        .list_sections(&page_id, after, before, first, last)
+                        .await
+                },
+            )
+            .await
+        })
         .await
     }
 
@@ -904,19 +1754,22 @@ impl Query {
         first: Option<i32>,
         last: Option<i32>,
     ) -> Result<Connection<CustomWebDocument>> {
-        check_admin(ctx).await?;
-        query_async(
-            after,
-            before,
-            first,
-            last,
-            |after, before, first, last| async move {
-                ctx.locator
-                    .web_documents()
-                    .list_custom_web_documents(ids, after, before, first, last)
-                    .await
-            },
-        )
+        instrumented(ctx, "custom_web_documents", async move {
+            let admin = check_admin(ctx).await?;
+            query_async(
+                after,
+                before,
+                first,
+                last,
+                |after, before, first, last| async move {
+                    admin.locator()
+                        .web_documents()
+                        .list_custom_web_documents(ids, after, before, first, last)
+                        .await
+                },
+            )
+            .await
+        })
         .await
     }
     async fn preset_web_documents(
@@ -928,129 +1781,193 @@ impl Query {
         last: Option<i32>,
         is_active: Option<bool>,
     ) -> Result<Connection<PresetWebDocument>> {
-        check_admin(ctx).await?;
-        query_async(
-            after,
-            before,
-            first,
-            last,
-            |after, before, first, last| async move {
-                ctx.locator
-                    .web_documents()
-                    .list_preset_web_documents(ids, after, before, first, last, is_active)
-                    .await
-            },
-        )
+        instrumented(ctx, "preset_web_documents", async move {
+            let admin = check_admin(ctx).await?;
+            query_async(
+                after,
+                before,
+                first,
+                last,
+                |after, before, first, last| async move {
+                    admin.locator()
+                        .web_documents()
+                        .list_preset_web_documents(ids, after, before, first, last, is_active)
+                        .await
+                },
+            )
+            .await
+        })
         .await
     }
 
     /// List user groups.
     async fn user_groups(ctx: &Context) -> Result<Vec<UserGroup>> {
-        check_user(ctx).await?;
-        ctx.locator.user_group().list().await
+        instrumented(ctx, "user_groups", async move {
+            let UserCtx(_, locator) = check_user(ctx).await?;
+            locator.user_group().list().await
+        })
+        .await
     }
 
     async fn source_id_access_policies(
         ctx: &Context,
         source_id: String,
     ) -> Result<SourceIdAccessPolicy> {
-        check_admin(ctx).await?;
-        let read = ctx
-            .locator
-            .access_policy()
-            .list_source_id_read_access(&source_id)
-            .await?;
+        instrumented(ctx, "source_id_access_policies", async move {
+            let admin = check_admin(ctx).await?;
+            let read = admin
+                .locator()
+                .access_policy()
+                .list_source_id_read_access(&source_id)
+                .await?;
+
+            Ok(SourceIdAccessPolicy { source_id, read })
+        })
+        .await
+    }
 
-        Ok(SourceIdAccessPolicy { source_id, read })
+    /// Run a one-shot health probe across every subsystem (SMTP, database, disk usage,
+    /// configured integrations, license status, and the chat/completion/embedding model
+    /// backends) so operators don't have to stitch together `server_info`, `disk_usage_stats`,
+    /// and `test_model_connection` themselves.
+    async fn diagnostics(ctx: &Context) -> Result<Diagnostics> {
+        instrumented(ctx, "diagnostics", async move {
+            let admin = check_admin(ctx).await?;
+
+            let (
+                smtp,
+                database,
+                disk_usage,
+                chat,
+                completion,
+                embedding,
+                integrations,
+                license,
+            ) = futures::join!(
+                probe_smtp(ctx),
+                probe_database(ctx),
+                timeout(
+                    DIAGNOSTIC_PROBE_TIMEOUT,
+                    admin.locator().analytic().disk_usage_stats()
+                ),
+                diagnose_chat(ctx),
+                diagnose_completion(ctx),
+                diagnose_embedding(ctx),
+                probe_integrations(ctx),
+                probe_license(ctx),
+            );
+
+            Ok(Diagnostics {
+                smtp,
+                database,
+                disk_usage: disk_usage.map_err(|_| {
+                    CoreError::Other(anyhow::anyhow!(
+                        "Timed out while running the diagnostic probe"
+                    ))
+                })??,
+                chat,
+                completion,
+                embedding,
+                integrations,
+                license,
+            })
+        })
+        .await
     }
 
     async fn test_model_connection(
         ctx: &Context,
         backend: ModelHealthBackend,
     ) -> Result<ModelBackendHealthInfo, TestModelConnectionError> {
-        check_admin(ctx).await?;
-
-        // count request time in milliseconds
-        let start = Instant::now();
-
-        match backend {
-            ModelHealthBackend::Completion => {
-                if let Some(completion) = ctx.locator.completion() {
-                    let config = CompletionConfig::default();
-                    let options = CompletionOptionsBuilder::default()
-                        .max_decoding_tokens(config.max_decoding_tokens as i32)
-                        .sampling_temperature(0.1)
-                        .seed(0)
-                        .build()
-                        .expect("Failed to build completion options");
-
-                    let (first, _) = completion
-                        .generate("def fib(n):\n", options)
-                        .await
-                        .into_future()
-                        .await;
-
-                    if first.is_some() {
-                        return Ok(ModelBackendHealthInfo {
-                            latency_ms: start.elapsed().as_millis() as i32,
-                        });
+        instrumented(ctx, "test_model_connection", async move {
+            let admin = check_admin(ctx).await?;
+
+            // count request time in milliseconds
+            let start = Instant::now();
+
+            match backend {
+                ModelHealthBackend::Completion => {
+                    if let Some(completion) = admin.locator().completion() {
+                        let config = CompletionConfig::default();
+                        let options = CompletionOptionsBuilder::default()
+                            .max_decoding_tokens(config.max_decoding_tokens as i32)
+                            .sampling_temperature(0.1)
+                            .seed(0)
+                            .build()
+                            .expect("Failed to build completion options");
+
+                        let (first, _) = completion
+                            .generate("def fib(n):\n", options)
+                            .await
+                            .into_future()
+                            .await;
+
+                        if first.is_some() {
+                            return Ok(ModelBackendHealthInfo {
+                                latency_ms: start.elapsed().as_millis() as i32,
+                            });
+                        }
+
+                        Err(TestModelConnectionError::FailedToConnect(
+                            "Failed to connect to the completion model".into(),
+                        ))
+                    } else {
+                        Err(TestModelConnectionError::NotEnabled)
                     }
-
-                    Err(TestModelConnectionError::FailedToConnect(
-                        "Failed to connect to the completion model".into(),
-                    ))
-                } else {
-                    Err(TestModelConnectionError::NotEnabled)
                 }
-            }
-            ModelHealthBackend::Chat => {
-                if let Some(chat) = ctx.locator.chat() {
-                    let request = CreateChatCompletionRequestArgs::default()
-                        .messages(vec![ChatCompletionRequestMessage::User(
-                            ChatCompletionRequestUserMessageArgs::default()
-                                .content("Hello, please reply in short")
-                                .build()
-                                .expect("Failed to build chat completion message"),
-                        )])
-                        .build()
-                        .expect("Failed to build chat completion request");
-                    match chat.chat(request).await {
+                ModelHealthBackend::Chat => {
+                    if let Some(chat) = admin.locator().chat() {
+                        let request = CreateChatCompletionRequestArgs::default()
+                            .messages(vec![ChatCompletionRequestMessage::User(
+                                ChatCompletionRequestUserMessageArgs::default()
+                                    .content("Hello, please reply in short")
+                                    .build()
+                                    .expect("Failed to build chat completion message"),
+                            )])
+                            .build()
+                            .expect("Failed to build chat completion request");
+                        match chat.chat(request).await {
+                            Ok(_) => Ok(ModelBackendHealthInfo {
+                                latency_ms: start.elapsed().as_millis() as i32,
+                            }),
+                            Err(e) => Err(e.into()),
+                        }
+                    } else {
+                        Err(TestModelConnectionError::NotEnabled)
+                    }
+                }
+                ModelHealthBackend::Embedding => {
+                    let embedding = admin.locator().embedding();
+                    match embedding.embed("hello Tabby").await {
                         Ok(_) => Ok(ModelBackendHealthInfo {
                             latency_ms: start.elapsed().as_millis() as i32,
                         }),
-                        Err(e) => Err(e.into()),
+                        Err(e) => Err(TestModelConnectionError::FailedToConnect(e.to_string())),
                     }
-                } else {
-                    Err(TestModelConnectionError::NotEnabled)
-                }
-            }
-            ModelHealthBackend::Embedding => {
-                let embedding = ctx.locator.embedding();
-                match embedding.embed("hello Tabby").await {
-                    Ok(_) => Ok(ModelBackendHealthInfo {
-                        latency_ms: start.elapsed().as_millis() as i32,
-                    }),
-                    Err(e) => Err(TestModelConnectionError::FailedToConnect(e.to_string())),
                 }
             }
-        }
+        })
+        .await
     }
 
     async fn read_repository_related_questions(
         ctx: &Context,
         source_id: String,
     ) -> Result<Vec<String>, CoreError> {
-        let user = check_user(ctx).await?;
-        ctx.locator
-            .repository()
-            .read_repository_related_questions(
-                ctx.locator
-                    .chat()
-                    .ok_or(CoreError::NotFound("The Chat didn't initialize yet"))?,
-                &user.policy,
-                source_id,
-            )
-            .await
+        instrumented(ctx, "read_repository_related_questions", async move {
+            let UserCtx(user, locator) = check_user(ctx).await?;
+            locator
+                .repository()
+                .read_repository_related_questions(
+                    locator
+                        .chat()
+                        .ok_or(CoreError::NotFound("The Chat didn't initialize yet"))?,
+                    &user.policy,
+                    source_id,
+                )
+                .await
+        })
+        .await
     }
 }
 
@@ -1062,6 +1979,31 @@ pub struct ServerInfo {
     allow_self_signup: bool,
     disable_password_login: bool,
     is_demo_mode: bool,
+    require_captcha: bool,
+}
+
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct PersonalAccessToken {
+    pub id: ID,
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Returned once, at creation time, since the raw token is never stored or shown again.
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct PersonalAccessTokenCreated {
+    pub id: ID,
+    pub token: String,
+}
+
+#[derive(GraphQLInputObject, Validate)]
+pub struct CreatePersonalAccessTokenInput {
+    #[validate(length(min = 1, max = 128))]
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Default)]
@@ -1070,94 +2012,191 @@ pub struct Mutation;
 #[graphql_object(context = Context)]
 impl Mutation {
     async fn reset_registration_token(ctx: &Context) -> Result<String> {
-        check_admin(ctx).await?;
-        ctx.locator.worker().reset_registration_token().await
+        instrumented(ctx, "reset_registration_token", async move {
+            let admin = check_admin(ctx).await?;
+            let token = admin.locator().worker().reset_registration_token().await?;
+            record_audit_log(
+                ctx,
+                AuditAction::RegistrationTokenReset,
+                None,
+                "Reset registration token".into(),
+            )
+            .await;
+            Ok(token)
+        })
+        .await
     }
 
     async fn request_invitation_email(
         ctx: &Context,
         input: RequestInvitationInput,
     ) -> Result<Invitation> {
-        input.validate()?;
-        ctx.locator.auth().request_invitation_email(input).await
+        instrumented(ctx, "request_invitation_email", async move {
+            input.validate()?;
+            ctx.locator.auth().request_invitation_email(input).await
+        })
+        .await
     }
 
     async fn generate_reset_password_url(ctx: &Context, user_id: ID) -> Result<String> {
-        check_admin(ctx).await?;
-        ctx.locator
-            .auth()
-            .generate_reset_password_url(&user_id)
-            .await
+        instrumented(ctx, "generate_reset_password_url", async move {
+            let admin = check_admin(ctx).await?;
+            let url = admin
+                .locator()
+                .auth()
+                .generate_reset_password_url(&user_id)
+                .await?;
+            record_audit_log(
+                ctx,
+                AuditAction::PasswordResetUrlGenerated,
+                Some(user_id.to_string()),
+                "Generated a password reset URL".into(),
+            )
+            .await;
+            Ok(url)
+        })
+        .await
     }
 
     async fn request_password_reset_email(
         ctx: &Context,
         input: RequestPasswordResetEmailInput,
     ) -> Result<bool> {
-        input.validate()?;
-        ctx.locator
-            .auth()
-            .request_password_reset_email(input.email)
-            .await?;
-        Ok(true)
+        instrumented(ctx, "request_password_reset_email", async move {
+            input.validate()?;
+            ctx.locator
+                .auth()
+                .request_password_reset_email(input.email)
+                .await?;
+            Ok(true)
+        })
+        .await
     }
 
     async fn password_reset(ctx: &Context, input: PasswordResetInput) -> Result<bool> {
-        input.validate()?;
-        ctx.locator
-            .auth()
-            .password_reset(&input.code, &input.password1)
-            .await?;
-        Ok(true)
+        instrumented(ctx, "password_reset", async move {
+            input.validate()?;
+            ctx.locator
+                .auth()
+                .password_reset(&input.code, &input.password1)
+                .await?;
+            Ok(true)
+        })
+        .await
     }
 
     async fn password_change(ctx: &Context, input: PasswordChangeInput) -> Result<bool> {
-        let claims = check_claims(ctx)?;
-        input.validate()?;
-        ctx.locator
-            .auth()
-            .update_user_password(
-                &claims.sub,
-                input.old_password.as_deref(),
-                &input.new_password1,
-            )
-            .await?;
-        Ok(true)
+        instrumented(ctx, "password_change", async move {
+            let claims = check_claims(ctx)?;
+            input.validate()?;
+            ctx.locator
+                .auth()
+                .update_user_password(
+                    &claims.sub,
+                    input.old_password.as_deref(),
+                    &input.new_password1,
+                )
+                .await?;
+            Ok(true)
+        })
+        .await
     }
 
     async fn reset_user_auth_token(ctx: &Context) -> Result<bool> {
-        let claims = check_claims(ctx)?;
-        ctx.locator
-            .auth()
-            .reset_user_auth_token(&claims.sub)
-            .await?;
-        Ok(true)
+        instrumented(ctx, "reset_user_auth_token", async move {
+            let claims = check_claims(ctx)?;
+            ctx.locator
+                .auth()
+                .reset_user_auth_token(&claims.sub)
+                .await?;
+            Ok(true)
+        })
+        .await
+    }
+
+    /// Mint a new scoped personal access token for the current user.
+    async fn create_personal_access_token(
+        ctx: &Context,
+        input: CreatePersonalAccessTokenInput,
+    ) -> Result<PersonalAccessTokenCreated> {
+        instrumented(ctx, "create_personal_access_token", async move {
+            let claims = check_claims(ctx)?;
+            input.validate()?;
+            let (id, token) = ctx
+                .locator
+                .auth()
+                .create_personal_access_token(
+                    &claims.sub,
+                    input.name,
+                    input.scopes,
+                    input.expires_at,
+                )
+                .await?;
+            Ok(PersonalAccessTokenCreated { id, token })
+        })
+        .await
+    }
+
+    async fn revoke_personal_access_token(ctx: &Context, id: ID) -> Result<bool> {
+        instrumented(ctx, "revoke_personal_access_token", async move {
+            let claims = check_claims(ctx)?;
+            ctx.locator
+                .auth()
+                .revoke_personal_access_token(&claims.sub, &id)
+                .await?;
+            Ok(true)
+        })
+        .await
     }
 
     async fn logout_all_sessions(ctx: &Context) -> Result<bool> {
-        let claims = check_claims(ctx)?;
-        ctx.locator.auth().logout_all_sessions(&claims.sub).await?;
-        Ok(true)
+        instrumented(ctx, "logout_all_sessions", async move {
+            let claims = check_claims(ctx)?;
+            ctx.locator.auth().logout_all_sessions(&claims.sub).await?;
+            Ok(true)
+        })
+        .await
     }
 
     async fn update_user_active(ctx: &Context, id: ID, active: bool) -> Result<bool> {
-        check_admin(ctx).await?;
-        if ctx.claims.as_ref().is_some_and(|c| c.sub == id) {
-            return Err(CoreError::Forbidden(
-                "You cannot change your own active status",
-            ));
-        }
-        ctx.locator.auth().update_user_active(&id, active).await?;
-        Ok(true)
+        instrumented(ctx, "update_user_active", async move {
+            let admin = check_admin(ctx).await?;
+            if ctx.claims.as_ref().is_some_and(|c| c.sub == id) {
+                return Err(CoreError::Forbidden(
+                    "You cannot change your own active status",
+                ));
+            }
+            admin.locator().auth().update_user_active(&id, active).await?;
+            record_audit_log(
+                ctx,
+                AuditAction::UserActiveUpdated,
+                Some(id.to_string()),
+                format!("Set user active status to {active}"),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
     }
 
     async fn update_user_role(ctx: &Context, id: ID, is_admin: bool) -> Result<bool> {
-        check_admin(ctx).await?;
-        if ctx.claims.as_ref().is_some_and(|c| c.sub == id) {
-            return Err(CoreError::Forbidden("You cannot update your own role"));
-        }
-        ctx.locator.auth().update_user_role(&id, is_admin).await?;
-        Ok(true)
+        instrumented(ctx, "update_user_role", async move {
+            let admin = check_admin(ctx).await?;
+            if ctx.claims.as_ref().is_some_and(|c| c.sub == id) {
+                return Err(CoreError::Forbidden("You cannot update your own role"));
+            }
+            admin.locator().auth().update_user_role(&id, is_admin).await?;
+            record_audit_log_with_metadata(
+                ctx,
+                AuditAction::UserRoleUpdated,
+                Some(id.to_string()),
+                format!("Set user admin status to {is_admin}"),
+                Some(serde_json::json!({ "is_admin": is_admin }).to_string()),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
     }
 
     async fn upload_user_avatar_base64(
@@ -1165,34 +2204,40 @@ impl Mutation {
         id: ID,
         avatar_base64: Option<String>,
     ) -> Result<bool> {
-        let claims = check_claims(ctx)?;
-        if claims.sub != id {
-            return Err(CoreError::Unauthorized(
-                "You cannot change another user's avatar",
-            ));
-        }
-        // ast-grep-ignore: use-schema-result
-        use anyhow::Context;
-        let avatar = avatar_base64
-            .map(|avatar| base64::prelude::BASE64_STANDARD.decode(avatar.as_bytes()))
-            .transpose()
-            .context("avatar is not valid base64 string")?
-            .map(Vec::into_boxed_slice);
-        ctx.locator.auth().update_user_avatar(&id, avatar).await?;
-        Ok(true)
-    }
+        instrumented(ctx, "upload_user_avatar_base64", async move {
+            let claims = check_claims(ctx)?;
+            if claims.sub != id {
+                return Err(CoreError::Unauthorized(
+                    "You cannot change another user's avatar",
+                ));
+            }
+            // ast-grep-ignore: use-schema-result
+            use anyhow::Context;
+            let avatar = avatar_base64
+                .map(|avatar| base64::prelude::BASE64_STANDARD.decode(avatar.as_bytes()))
+                .transpose()
+                .context("avatar is not valid base64 string")?
+                .map(Vec::into_boxed_slice);
+            ctx.locator.auth().update_user_avatar(&id, avatar).await?;
+            Ok(true)
+        })
+        .await
+    }
 
     async fn update_user_name(ctx: &Context, id: ID, name: String) -> Result<bool> {
-        let claims = check_claims(ctx)?;
-        if claims.sub != id {
-            return Err(CoreError::Unauthorized(
-                "You cannot change another user's name",
-            ));
-        }
-        let input = auth::UpdateUserNameInput { name };
-        input.validate()?;
-        ctx.locator.auth().update_user_name(&id, input.name).await?;
-        Ok(true)
+        instrumented(ctx, "update_user_name", async move {
+            let claims = check_claims(ctx)?;
+            if claims.sub != id {
+                return Err(CoreError::Unauthorized(
+                    "You cannot change another user's name",
+                ));
+            }
+            let input = auth::UpdateUserNameInput { name };
+            input.validate()?;
+            ctx.locator.auth().update_user_name(&id, input.name).await?;
+            Ok(true)
+        })
+        .await
     }
 
     async fn register(
@@ -1202,94 +2247,206 @@ impl Mutation {
         password2: String,
         invitation_code: Option<String>,
         name: String,
+        captcha_uuid: Option<String>,
+        captcha_answer: Option<String>,
     ) -> Result<RegisterResponse> {
-        let input = auth::RegisterInput {
-            email,
-            password1,
-            password2,
-        };
-        input.validate()?;
-
-        ctx.locator
-            .auth()
-            .register(input.email, input.password1, invitation_code, Some(name))
-            .await
+        instrumented(ctx, "register", async move {
+            ctx.locator
+                .captcha()
+                .verify_challenge(captcha_uuid, captcha_answer)
+                .await?;
+
+            let input = auth::RegisterInput {
+                email,
+                password1,
+                password2,
+            };
+            input.validate()?;
+
+            ctx.locator
+                .auth()
+                .register(input.email, input.password1, invitation_code, Some(name))
+                .await
+        })
+        .await
     }
 
     async fn token_auth(
         ctx: &Context,
         email: String,
         password: String,
-    ) -> Result<TokenAuthResponse> {
-        let input = auth::TokenAuthInput { email, password };
-        input.validate()?;
-        ctx.locator
-            .auth()
-            .token_auth(input.email, input.password)
-            .await
+    ) -> Result<TokenAuthResult> {
+        instrumented(ctx, "token_auth", async move {
+            let input = auth::TokenAuthInput { email, password };
+            input.validate()?;
+            let outcome = ctx
+                .locator
+                .auth()
+                .token_auth(input.email, input.password)
+                .await?;
+            Ok(outcome.into())
+        })
+        .await
     }
 
     async fn token_auth_ldap(
         ctx: &Context,
         user_id: String,
         password: String,
+    ) -> Result<TokenAuthResult> {
+        instrumented(ctx, "token_auth_ldap", async move {
+            let input = auth::TokenAuthLdapInput {
+                user_id: &user_id,
+                password: &password,
+            };
+            input.validate()?;
+            let outcome = ctx
+                .locator
+                .auth()
+                .token_auth_ldap(&user_id, &password)
+                .await?;
+            Ok(outcome.into())
+        })
+        .await
+    }
+
+    async fn token_auth_oidc(ctx: &Context, code: String, state: String) -> Result<TokenAuthResult> {
+        instrumented(ctx, "token_auth_oidc", async move {
+            let outcome = ctx.locator.oidc().exchange_code(code, state).await?;
+            Ok(outcome.into())
+        })
+        .await
+    }
+
+    async fn token_auth_verify_totp(
+        ctx: &Context,
+        pending_session: String,
+        code: String,
     ) -> Result<TokenAuthResponse> {
-        let input = auth::TokenAuthLdapInput {
-            user_id: &user_id,
-            password: &password,
-        };
-        input.validate()?;
-        ctx.locator
-            .auth()
-            .token_auth_ldap(&user_id, &password)
-            .await
+        instrumented(ctx, "token_auth_verify_totp", async move {
+            ctx.locator
+                .auth()
+                .verify_totp(pending_session, code)
+                .await
+        })
+        .await
+    }
+
+    async fn generate_totp_secret(ctx: &Context) -> Result<TotpSecret> {
+        instrumented(ctx, "generate_totp_secret", async move {
+            let UserCtx(user, locator) = check_user(ctx).await?;
+            locator.auth().generate_totp_secret(&user.id).await
+        })
+        .await
+    }
+
+    async fn enable_totp(ctx: &Context, code: String) -> Result<TotpRecoveryCodes> {
+        instrumented(ctx, "enable_totp", async move {
+            let UserCtx(user, locator) = check_user(ctx).await?;
+            locator.auth().enable_totp(&user.id, code).await
+        })
+        .await
+    }
+
+    async fn disable_totp(ctx: &Context, code: String) -> Result<bool> {
+        instrumented(ctx, "disable_totp", async move {
+            let UserCtx(user, locator) = check_user(ctx).await?;
+            locator.auth().disable_totp(&user.id, code).await?;
+            Ok(true)
+        })
+        .await
     }
 
     async fn verify_token(ctx: &Context, token: String) -> Result<bool> {
-        ctx.locator.auth().verify_access_token(&token).await?;
-        Ok(true)
+        instrumented(ctx, "verify_token", async move {
+            ctx.locator.auth().verify_access_token(&token).await?;
+            Ok(true)
+        })
+        .await
     }
 
     async fn refresh_token(ctx: &Context, refresh_token: String) -> Result<RefreshTokenResponse> {
-        ctx.locator.auth().refresh_token(refresh_token).await
+        instrumented(ctx, "refresh_token", async move {
+            ctx.locator.auth().refresh_token(refresh_token).await
+        })
+        .await
     }
 
     async fn create_invitation(ctx: &Context, email: String) -> Result<ID> {
-        check_admin(ctx).await?;
-        let invitation = ctx.locator.auth().create_invitation(email.clone()).await?;
-        Ok(invitation.id)
+        instrumented(ctx, "create_invitation", async move {
+            let admin = check_admin(ctx).await?;
+            let invitation = admin.locator().auth().create_invitation(email.clone()).await?;
+            record_audit_log(
+                ctx,
+                AuditAction::InvitationCreated,
+                Some(invitation.id.to_string()),
+                format!("Invited {email}"),
+            )
+            .await;
+            Ok(invitation.id)
+        })
+        .await
     }
 
     async fn send_test_email(ctx: &Context, to: String) -> Result<bool> {
-        check_admin(ctx).await?;
-        ctx.locator.email().send_test(to).await?;
-        Ok(true)
+        instrumented(ctx, "send_test_email", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().email().send_test(to).await?;
+            Ok(true)
+        })
+        .await
     }
 
     async fn mark_notifications_read(ctx: &Context, notification_id: Option<ID>) -> Result<bool> {
-        let user = check_user(ctx).await?;
-
-        ctx.locator
-            .notification()
-            .mark_read(&user.id, notification_id.as_ref())
-            .await?;
-        Ok(true)
+        instrumented(ctx, "mark_notifications_read", async move {
+            let UserCtx(user, locator) = check_user(ctx).await?;
+
+            locator
+                .notification()
+                .mark_read(&user.id, notification_id.as_ref())
+                .await?;
+            Ok(true)
+        })
+        .await
     }
 
     async fn create_git_repository(ctx: &Context, name: String, git_url: String) -> Result<ID> {
-        check_admin(ctx).await?;
-        let input = repository::CreateGitRepositoryInput { name, git_url };
-        input.validate()?;
-        ctx.locator
-            .repository()
-            .git()
-            .create(input.name, input.git_url)
-            .await
+        instrumented(ctx, "create_git_repository", async move {
+            let admin = check_admin(ctx).await?;
+            let input = repository::CreateGitRepositoryInput { name, git_url };
+            input.validate()?;
+            let id = admin
+                .locator()
+                .repository()
+                .git()
+                .create(input.name, input.git_url)
+                .await?;
+            record_audit_log(
+                ctx,
+                AuditAction::GitRepositoryCreated,
+                Some(id.to_string()),
+                "Created git repository".into(),
+            )
+            .await;
+            Ok(id)
+        })
+        .await
     }
 
     async fn delete_git_repository(ctx: &Context, id: ID) -> Result<bool> {
-        check_admin(ctx).await?;
-        ctx.locator.repository().git().delete(&id).await
+        instrumented(ctx, "delete_git_repository", async move {
+            let admin = check_admin(ctx).await?;
+            let deleted = admin.locator().repository().git().delete(&id).await?;
+            record_audit_log(
+                ctx,
+                AuditAction::GitRepositoryDeleted,
+                Some(id.to_string()),
+                "Deleted git repository".into(),
+            )
+            .await;
+            Ok(deleted)
+        })
+        .await
     }
 
     async fn update_git_repository(
@@ -1298,140 +2455,429 @@ impl Mutation {
         name: String,
         git_url: String,
     ) -> Result<bool> {
-        check_admin(ctx).await?;
-        ctx.locator
-            .repository()
-            .git()
-            .update(&id, name, git_url)
-            .await
+        instrumented(ctx, "update_git_repository", async move {
+            let admin = check_admin(ctx).await?;
+            let updated = admin
+                .locator()
+                .repository()
+                .git()
+                .update(&id, name, git_url)
+                .await?;
+            record_audit_log(
+                ctx,
+                AuditAction::GitRepositoryUpdated,
+                Some(id.to_string()),
+                "Updated git repository".into(),
+            )
+            .await;
+            Ok(updated)
+        })
+        .await
     }
 
     async fn delete_invitation(ctx: &Context, id: ID) -> Result<ID> {
-        check_admin(ctx).await?;
-        ctx.locator.auth().delete_invitation(&id).await
+        instrumented(ctx, "delete_invitation", async move {
+            let admin = check_admin(ctx).await?;
+            let id = admin.locator().auth().delete_invitation(&id).await?;
+            record_audit_log(
+                ctx,
+                AuditAction::InvitationDeleted,
+                Some(id.to_string()),
+                "Deleted invitation".into(),
+            )
+            .await;
+            Ok(id)
+        })
+        .await
     }
 
     async fn update_oauth_credential(
         ctx: &Context,
         input: UpdateOAuthCredentialInput,
     ) -> Result<bool> {
-        check_admin(ctx).await?;
-        check_license(ctx, &[LicenseType::Enterprise]).await?;
-        input.validate()?;
-        ctx.locator.auth().update_oauth_credential(input).await?;
-        Ok(true)
+        instrumented(ctx, "update_oauth_credential", async move {
+            let admin = check_admin(ctx).await?;
+            check_license(ctx, &[LicenseType::Enterprise]).await?;
+            input.validate()?;
+            let provider = input.provider;
+            admin.locator().auth().update_oauth_credential(input).await?;
+            record_audit_log(
+                ctx,
+                AuditAction::OAuthCredentialUpdated,
+                None,
+                format!("Updated OAuth credential for {provider:?}"),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
     }
 
     async fn delete_oauth_credential(ctx: &Context, provider: OAuthProvider) -> Result<bool> {
-        check_admin(ctx).await?;
-        ctx.locator.auth().delete_oauth_credential(provider).await?;
-        Ok(true)
+        instrumented(ctx, "delete_oauth_credential", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator()
+                .auth()
+                .delete_oauth_credential(provider)
+                .await?;
+            record_audit_log(
+                ctx,
+                AuditAction::OAuthCredentialUpdated,
+                None,
+                format!("Deleted OAuth credential for {provider:?}"),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
+    }
+
+    async fn update_oidc_credential(
+        ctx: &Context,
+        input: UpdateOidcCredentialInput,
+    ) -> Result<bool> {
+        instrumented(ctx, "update_oidc_credential", async move {
+            let admin = check_admin(ctx).await?;
+            check_license(ctx, &[LicenseType::Enterprise]).await?;
+            input.validate()?;
+            admin.locator().oidc().update_credential(input).await?;
+            record_audit_log(
+                ctx,
+                AuditAction::OidcCredentialUpdated,
+                None,
+                "Updated OIDC credential".into(),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
+    }
+
+    async fn delete_oidc_credential(ctx: &Context) -> Result<bool> {
+        instrumented(ctx, "delete_oidc_credential", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().oidc().delete_credential().await?;
+            record_audit_log(
+                ctx,
+                AuditAction::OidcCredentialUpdated,
+                None,
+                "Deleted OIDC credential".into(),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
     }
 
     async fn test_ldap_connection(ctx: &Context, input: UpdateLdapCredentialInput) -> Result<bool> {
-        check_admin(ctx).await?;
-        check_license(ctx, &[LicenseType::Enterprise]).await?;
-        ctx.locator.auth().test_ldap_connection(input).await?;
-        Ok(true)
+        instrumented(ctx, "test_ldap_connection", async move {
+            let admin = check_admin(ctx).await?;
+            check_license(ctx, &[LicenseType::Enterprise]).await?;
+            admin.locator().auth().test_ldap_connection(input).await?;
+            Ok(true)
+        })
+        .await
     }
 
     async fn update_ldap_credential(
         ctx: &Context,
         input: UpdateLdapCredentialInput,
     ) -> Result<bool> {
-        check_admin(ctx).await?;
-        check_license(ctx, &[LicenseType::Enterprise]).await?;
-        input.validate()?;
-
-        ctx.locator.auth().update_ldap_credential(input).await?;
-        Ok(true)
+        instrumented(ctx, "update_ldap_credential", async move {
+            let admin = check_admin(ctx).await?;
+            check_license(ctx, &[LicenseType::Enterprise]).await?;
+            input.validate()?;
+
+            admin.locator().auth().update_ldap_credential(input).await?;
+            record_audit_log(
+                ctx,
+                AuditAction::LdapCredentialUpdated,
+                None,
+                "Updated LDAP credential".into(),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
     }
 
     async fn delete_ldap_credential(ctx: &Context) -> Result<bool> {
-        check_admin(ctx).await?;
-        ctx.locator.auth().delete_ldap_credential().await?;
-        Ok(true)
+        instrumented(ctx, "delete_ldap_credential", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().auth().delete_ldap_credential().await?;
+            record_audit_log(
+                ctx,
+                AuditAction::LdapCredentialUpdated,
+                None,
+                "Deleted LDAP credential".into(),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
     }
 
     async fn update_email_setting(ctx: &Context, input: EmailSettingInput) -> Result<bool> {
-        check_admin(ctx).await?;
-        input.validate()?;
-        ctx.locator.email().update_setting(input).await?;
-        Ok(true)
+        instrumented(ctx, "update_email_setting", async move {
+            let admin = check_admin(ctx).await?;
+            input.validate()?;
+            admin.locator().email().update_setting(input).await?;
+            record_audit_log(
+                ctx,
+                AuditAction::EmailSettingUpdated,
+                None,
+                "Updated email setting".into(),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
     }
 
-    async fn update_security_setting(ctx: &Context, input: SecuritySettingInput) -> Result<bool> {
-        check_admin(ctx).await?;
-        check_license(ctx, &[LicenseType::Enterprise]).await?;
-        input.validate()?;
-        ctx.locator.setting().update_security_setting(input).await?;
-        Ok(true)
+    async fn update_security_setting(
+        ctx: &Context,
+        input: SecuritySettingInput,
+    ) -> Result<UpdateSecuritySettingResult> {
+        instrumented(ctx, "update_security_setting", async move {
+            let admin = check_admin(ctx).await?;
+            check_license(ctx, &[LicenseType::Enterprise]).await?;
+            input.validate()?;
+            let locked_out_user_count = if input.require_two_factor {
+                admin.locator().auth().count_users_without_totp().await?
+            } else {
+                0
+            };
+            admin.locator()
+                .setting()
+                .update_security_setting(input)
+                .await?;
+            record_audit_log(
+                ctx,
+                AuditAction::SecuritySettingUpdated,
+                None,
+                "Updated security setting".into(),
+            )
+            .await;
+            Ok(UpdateSecuritySettingResult {
+                ok: true,
+                locked_out_user_count,
+            })
+        })
+        .await
     }
 
     async fn update_network_setting(ctx: &Context, input: NetworkSettingInput) -> Result<bool> {
-        check_admin(ctx).await?;
-        input.validate()?;
-        ctx.locator.setting().update_network_setting(input).await?;
-        Ok(true)
+        instrumented(ctx, "update_network_setting", async move {
+            let admin = check_admin(ctx).await?;
+            input.validate()?;
+            admin.locator().setting().update_network_setting(input).await?;
+            record_audit_log(
+                ctx,
+                AuditAction::NetworkSettingUpdated,
+                None,
+                "Updated network setting".into(),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
     }
 
     async fn delete_email_setting(ctx: &Context) -> Result<bool> {
-        check_admin(ctx).await?;
-        ctx.locator.email().delete_setting().await?;
-        Ok(true)
+        instrumented(ctx, "delete_email_setting", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().email().delete_setting().await?;
+            record_audit_log(
+                ctx,
+                AuditAction::EmailSettingDeleted,
+                None,
+                "Deleted email setting".into(),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
+    }
+
+    async fn update_captcha_setting(ctx: &Context, input: CaptchaSettingInput) -> Result<bool> {
+        instrumented(ctx, "update_captcha_setting", async move {
+            let admin = check_admin(ctx).await?;
+            input.validate()?;
+            admin.locator().captcha().update_setting(input).await?;
+            record_audit_log(
+                ctx,
+                AuditAction::CaptchaSettingUpdated,
+                None,
+                "Updated captcha setting".into(),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
+    }
+
+    async fn delete_captcha_setting(ctx: &Context) -> Result<bool> {
+        instrumented(ctx, "delete_captcha_setting", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().captcha().delete_setting().await?;
+            record_audit_log(
+                ctx,
+                AuditAction::CaptchaSettingDeleted,
+                None,
+                "Deleted captcha setting".into(),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
     }
 
     async fn upload_license(ctx: &Context, license: String) -> Result<bool> {
-        check_admin(ctx).await?;
-        ctx.locator.license().update(license).await?;
-        Ok(true)
+        instrumented(ctx, "upload_license", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().license().update(license).await?;
+            record_audit_log(ctx, AuditAction::LicenseUpdated, None, "Uploaded license".into()).await;
+            Ok(true)
+        })
+        .await
+    }
+
+    async fn backup_database(ctx: &Context) -> Result<BackupArchive> {
+        instrumented(ctx, "backup_database", async move {
+            let admin = check_admin(ctx).await?;
+            let archive = admin.locator().backup().create().await?;
+            record_audit_log(
+                ctx,
+                AuditAction::BackupCreated,
+                Some(archive.id.to_string()),
+                format!("Created backup archive at {}", archive.path),
+            )
+            .await;
+            Ok(archive)
+        })
+        .await
+    }
+
+    async fn request_backup_restore(ctx: &Context, id: ID) -> Result<String> {
+        instrumented(ctx, "request_backup_restore", async move {
+            let admin = check_admin(ctx).await?;
+            let token = admin.locator().backup().request_restore(&id).await?;
+            record_audit_log(
+                ctx,
+                AuditAction::BackupRestoreRequested,
+                Some(id.to_string()),
+                "Requested restore of backup archive".into(),
+            )
+            .await;
+            Ok(token)
+        })
+        .await
+    }
+
+    async fn restore_backup(
+        ctx: &Context,
+        id: ID,
+        confirmation_token: String,
+    ) -> Result<bool> {
+        instrumented(ctx, "restore_backup", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator()
+                .backup()
+                .restore(&id, &confirmation_token)
+                .await?;
+            record_audit_log(
+                ctx,
+                AuditAction::BackupRestored,
+                Some(id.to_string()),
+                "Restored backup archive".into(),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
     }
 
     async fn reset_license(ctx: &Context) -> Result<bool> {
-        check_admin(ctx).await?;
-        ctx.locator.license().reset().await?;
-        Ok(true)
+        instrumented(ctx, "reset_license", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator().license().reset().await?;
+            record_audit_log(ctx, AuditAction::LicenseReset, None, "Reset license".into()).await;
+            Ok(true)
+        })
+        .await
     }
 
     async fn create_integration(ctx: &Context, input: CreateIntegrationInput) -> Result<ID> {
-        check_admin(ctx).await?;
-        input.validate()?;
-        let id = ctx
-            .locator
-            .integration()
-            .create_integration(
-                input.kind,
-                input.display_name,
-                input.access_token,
-                input.api_base,
+        instrumented(ctx, "create_integration", async move {
+            let admin = check_admin(ctx).await?;
+            input.validate()?;
+            let kind = input.kind;
+            let id = admin
+                .locator()
+                .integration()
+                .create_integration(
+                    input.kind,
+                    input.display_name,
+                    input.access_token,
+                    input.api_base,
+                )
+                .await?;
+            record_audit_log(
+                ctx,
+                AuditAction::IntegrationCreated,
+                Some(id.to_string()),
+                format!("Created {kind:?} integration"),
             )
-            .await?;
-        Ok(id)
+            .await;
+            Ok(id)
+        })
+        .await
     }
 
     async fn update_integration(ctx: &Context, input: UpdateIntegrationInput) -> Result<bool> {
-        check_admin(ctx).await?;
-        input.validate()?;
-        ctx.locator
-            .integration()
-            .update_integration(
-                input.id,
-                input.kind,
-                input.display_name,
-                input.access_token,
-                input.api_base,
+        instrumented(ctx, "update_integration", async move {
+            let admin = check_admin(ctx).await?;
+            input.validate()?;
+            let id = input.id.clone();
+            admin.locator()
+                .integration()
+                .update_integration(
+                    input.id,
+                    input.kind,
+                    input.display_name,
+                    input.access_token,
+                    input.api_base,
+                )
+                .await?;
+            record_audit_log(
+                ctx,
+                AuditAction::IntegrationUpdated,
+                Some(id.to_string()),
+                "Updated integration".into(),
             )
-            .await?;
-        Ok(true)
+            .await;
+            Ok(true)
+        })
+        .await
     }
 
     async fn delete_integration(ctx: &Context, id: ID, kind: IntegrationKind) -> Result<bool> {
-        check_admin(ctx).await?;
-        ctx.locator
-            .integration()
-            .delete_integration(id, kind)
-            .await?;
-        Ok(true)
+        instrumented(ctx, "delete_integration", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator()
+                .integration()
+                .delete_integration(id.clone(), kind)
+                .await?;
+            record_audit_log(
+                ctx,
+                AuditAction::IntegrationDeleted,
+                Some(id.to_string()),
+                "Deleted integration".into(),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
     }
 
     async fn update_integrated_repository_active(
@@ -1439,19 +2885,40 @@ impl Mutation {
         id: ID,
         active: bool,
     ) -> Result<bool> {
-        check_admin(ctx).await?;
-        ctx.locator
-            .repository()
-            .third_party()
-            .update_repository_active(id, active)
-            .await?;
-        Ok(true)
+        instrumented(ctx, "update_integrated_repository_active", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator()
+                .repository()
+                .third_party()
+                .update_repository_active(id.clone(), active)
+                .await?;
+            record_audit_log(
+                ctx,
+                AuditAction::IntegratedRepositoryActiveUpdated,
+                Some(id.to_string()),
+                format!("Set integrated repository active to {active}"),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
     }
 
     /// Trigger a job run given its param string.
     async fn trigger_job_run(ctx: &Context, command: String) -> Result<ID> {
-        check_admin(ctx).await?;
-        ctx.locator.job().trigger(command).await
+        instrumented(ctx, "trigger_job_run", async move {
+            let admin = check_admin(ctx).await?;
+            let id = admin.locator().job().trigger(command.clone()).await?;
+            record_audit_log(
+                ctx,
+                AuditAction::JobRunTriggered,
+                Some(id.to_string()),
+                format!("Triggered job run: {command}"),
+            )
+            .await;
+            Ok(id)
+        })
+        .await
     }
 
     /// Delete pair of user message and bot response in a thread.
@@ -1461,183 +2928,201 @@ impl Mutation {
         user_message_id: ID,
         assistant_message_id: ID,
     ) -> Result<bool> {
-        let user = check_user_allow_auth_token(ctx).await?;
-        let svc = ctx.locator.thread();
-        let Some(thread) = svc.get(&thread_id).await? else {
-            return Err(CoreError::NotFound("Thread not found"));
-        };
-
-        user.policy.check_delete_thread_messages(&thread.user_id)?;
-
-        ctx.locator
-            .thread()
-            .delete_thread_message_pair(&thread_id, &user_message_id, &assistant_message_id)
-            .await?;
-        Ok(true)
+        instrumented(ctx, "delete_thread_message_pair", async move {
+            let AuthTokenCtx(user, locator) = check_user_with_scope(ctx, Scope::ThreadsWrite).await?;
+            let svc = locator.thread();
+            let Some(thread) = svc.get(&thread_id).await? else {
+                return Err(CoreError::NotFound("Thread not found"));
+            };
+
+            user.policy.check_delete_thread_messages(&thread.user_id)?;
+
+            locator
+                .thread()
+                .delete_thread_message_pair(&thread_id, &user_message_id, &assistant_message_id)
+                .await?;
+            Ok(true)
+        })
+        .await
     }
 
     async fn delete_thread(ctx: &Context, id: ID) -> Result<bool> {
-        let user = check_user_allow_auth_token(ctx).await?;
-        let svc = ctx.locator.thread();
-        let Some(thread) = svc.get(&id).await? else {
-            return Err(CoreError::NotFound("Thread not found"));
-        };
+        instrumented(ctx, "delete_thread", async move {
+            let AuthTokenCtx(user, locator) = check_user_with_scope(ctx, Scope::ThreadsWrite).await?;
+            let svc = locator.thread();
+            let Some(thread) = svc.get(&id).await? else {
+                return Err(CoreError::NotFound("Thread not found"));
+            };
 
-        user.policy.check_delete_thread(&thread.user_id)?;
+            user.policy.check_delete_thread(&thread.user_id)?;
 
-        ctx.locator.thread().delete(&id).await?;
-        Ok(true)
+            locator.thread().delete(&id).await?;
+            Ok(true)
+        })
+        .await
     }
 
     /// Turn on persisted status for a thread.
     async fn set_thread_persisted(ctx: &Context, thread_id: ID) -> Result<bool> {
-        let user = check_user_allow_auth_token(ctx).await?;
-        let svc = ctx.locator.thread();
-        let Some(thread) = svc.get(&thread_id).await? else {
-            return Err(CoreError::NotFound("Thread not found"));
-        };
+        instrumented(ctx, "set_thread_persisted", async move {
+            let AuthTokenCtx(user, locator) = check_user_with_scope(ctx, Scope::ThreadsWrite).await?;
+            let svc = locator.thread();
+            let Some(thread) = svc.get(&thread_id).await? else {
+                return Err(CoreError::NotFound("Thread not found"));
+            };
 
-        user.policy
-            .check_update_thread_persistence(&thread.user_id)?;
+            user.policy
+                .check_update_thread_persistence(&thread.user_id)?;
 
-        ctx.locator.thread().set_persisted(&thread_id).await?;
-        Ok(true)
+            locator.thread().set_persisted(&thread_id).await?;
+            Ok(true)
+        })
+        .await
     }
 
     async fn update_thread_message(
         ctx: &Context,
         input: thread::UpdateMessageInput,
     ) -> Result<bool> {
-        let user = check_user(ctx).await?;
-        input.validate()?;
+        instrumented(ctx, "update_thread_message", async move {
+            let UserCtx(user, locator) = check_user(ctx).await?;
+            input.validate()?;
 
-        let svc = ctx.locator.thread();
-        let Some(thread) = svc.get(&input.thread_id).await? else {
-            return Err(CoreError::NotFound("Thread not found"));
-        };
+            let svc = locator.thread();
+            let Some(thread) = svc.get(&input.thread_id).await? else {
+                return Err(CoreError::NotFound("Thread not found"));
+            };
 
-        user.policy.check_update_thread_message(&thread.user_id)?;
+            user.policy.check_update_thread_message(&thread.user_id)?;
 
-        svc.update_thread_message(&input).await?;
-        Ok(true)
+            svc.update_thread_message(&input).await?;
+            Ok(true)
+        })
+        .await
     }
 
     // page mutations
     async fn update_page_title(ctx: &Context, input: UpdatePageTitleInput) -> Result<bool> {
-        let user = check_user(ctx).await?;
+        instrumented(ctx, "update_page_title", async move {
+            let page_service = require_page_service(ctx)?;
+            input.validate()?;
 
-        let page_service = if let Some(service) = ctx.locator.page() {
-            service
-        } else {
-            return Err(CoreError::Forbidden("Page service is not enabled"));
-        };
-        input.validate()?;
-
-        let page = page_service.get(&input.id).await?;
+            let (_page, proof) = authorize_update_page(ctx, &*page_service, &input.id).await?;
 
-        user.policy.check_update_page(&page.author_id)?;
-
-        page_service.update_title(&input.id, &input.title).await?;
-        Ok(true)
+            page_service
+                .update_title(&input.id, &input.title, proof)
+                .await?;
+            publish_page_event(ctx, &input.id, PageEventKind::TitleChanged, None).await;
+            Ok(true)
+        })
+        .await
     }
 
     async fn update_page_content(ctx: &Context, input: UpdatePageContentInput) -> Result<bool> {
-        let user = check_user(ctx).await?;
+        instrumented(ctx, "update_page_content", async move {
+            let page_service = require_page_service(ctx)?;
+            input.validate()?;
 
-        let page_service = if let Some(service) = ctx.locator.page() {
-            service
-        } else {
-            return Err(CoreError::Forbidden("Page service is not enabled"));
-        };
-        input.validate()?;
-
-        let page = page_service.get(&input.id).await?;
+            let (_page, proof) = authorize_update_page(ctx, &*page_service, &input.id).await?;
 
-        user.policy.check_update_page(&page.author_id)?;
-
-        page_service
-            .update_content(&input.id, &input.content)
-            .await?;
-        Ok(true)
+            page_service
+                .update_content(&input.id, &input.content, proof)
+                .await?;
+            publish_page_event(ctx, &input.id, PageEventKind::ContentChanged, None).await;
+            Ok(true)
+        })
+        .await
     }
 
     async fn update_page_section_title(
         ctx: &Context,
         input: UpdatePageSectionTitleInput,
     ) -> Result<bool> {
-        let user = check_user(ctx).await?;
-
-        let page_service = if let Some(service) = ctx.locator.page() {
-            service
-        } else {
-            return Err(CoreError::Forbidden("Page service is not enabled"));
-        };
-        input.validate()?;
-
-        let section = page_service.get_section(&input.id).await?;
-
-        let page = page_service.get(&section.page_id).await?;
-        user.policy.check_update_page(&page.author_id)?;
-
-        page_service
-            .update_section_title(&input.id, &input.title)
-            .await?;
-        Ok(true)
+        instrumented(ctx, "update_page_section_title", async move {
+            let page_service = require_page_service(ctx)?;
+            input.validate()?;
+
+            let section = page_service.get_section(&input.id).await?;
+            let (_page, proof) = authorize_update_page(ctx, &*page_service, &section.page_id).await?;
+
+            page_service
+                .update_section_title(&input.id, &input.title, proof)
+                .await?;
+            publish_page_event(
+                ctx,
+                &section.page_id,
+                PageEventKind::SectionUpdated,
+                Some(input.id),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
     }
 
     async fn update_page_section_content(
         ctx: &Context,
         input: UpdatePageSectionContentInput,
     ) -> Result<bool> {
-        let user = check_user(ctx).await?;
-
-        let page_service = if let Some(service) = ctx.locator.page() {
-            service
-        } else {
-            return Err(CoreError::Forbidden("Page service is not enabled"));
-        };
-        input.validate()?;
-
-        let section = page_service.get_section(&input.id).await?;
-        let page = page_service.get(&section.page_id).await?;
-        user.policy.check_update_page(&page.author_id)?;
-        page_service
-            .update_section_content(&input.id, &input.content)
-            .await?;
-        Ok(true)
+        instrumented(ctx, "update_page_section_content", async move {
+            let page_service = require_page_service(ctx)?;
+            input.validate()?;
+
+            let section = page_service.get_section(&input.id).await?;
+            let (_page, proof) = authorize_update_page(ctx, &*page_service, &section.page_id).await?;
+
+            page_service
+                .update_section_content(&input.id, &input.content, proof)
+                .await?;
+            publish_page_event(
+                ctx,
+                &section.page_id,
+                PageEventKind::SectionUpdated,
+                Some(input.id),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
     }
 
     /// delete a page and all its sections.
     async fn delete_page(ctx: &Context, id: ID) -> Result<bool> {
-        let user = check_user(ctx).await?;
-
-        let page_service = if let Some(service) = ctx.locator.page() {
-            service
-        } else {
-            return Err(CoreError::Forbidden("Page service is not enabled"));
-        };
-
-        let page = page_service.get(&id).await?;
-
-        user.policy.check_update_page(&page.author_id)?;
-        page_service.delete(&id).await.map(|_| true)
+        instrumented(ctx, "delete_page", async move {
+            let page_service = require_page_service(ctx)?;
+
+            let (_page, proof) = authorize_update_page(ctx, &*page_service, &id).await?;
+
+            let result = page_service.delete(&id, proof).await;
+            fire_mutation_hook(
+                ctx,
+                AuditAction::PageDeleted,
+                "delete_page",
+                Some(id.to_string()),
+                "Deleted page".into(),
+                &result,
+            )
+            .await;
+            result?;
+            Ok(true)
+        })
+        .await
     }
 
     /// delete a single page section.
     async fn delete_page_section(ctx: &Context, section_id: ID) -> Result<bool> {
-        let user = check_user(ctx).await?;
-
-        let page_service = if let Some(service) = ctx.locator.page() {
-            service
-        } else {
-            return Err(CoreError::Forbidden("Page service is not enabled"));
-        };
-        let section = page_service.get_section(&section_id).await?;
+        instrumented(ctx, "delete_page_section", async move {
+            let page_service = require_page_service(ctx)?;
 
-        let page = page_service.get(&section.page_id).await?;
-        user.policy.check_update_page(&page.author_id)?;
+            let section = page_service.get_section(&section_id).await?;
+            let (_page, proof) = authorize_update_page(ctx, &*page_service, &section.page_id).await?;
 
-        page_service.delete_section(&section_id).await.map(|_| true)
+            let page_id = section.page_id.clone();
+            page_service.delete_section(&section_id, proof).await?;
+            publish_page_event(ctx, &page_id, PageEventKind::SectionDeleted, Some(section_id)).await;
+            Ok(true)
+        })
+        .await
     }
 
     async fn move_page_section(
@@ -1645,82 +3130,168 @@ impl Mutation {
         id: ID,
         direction: page::MoveSectionDirection,
     ) -> Result<bool> {
-        let user = check_user(ctx).await?;
+        instrumented(ctx, "move_page_section", async move {
+            let page_service = require_page_service(ctx)?;
 
-        let page_service = if let Some(service) = ctx.locator.page() {
-            service
-        } else {
-            return Err(CoreError::Forbidden("Page service is not enabled"));
-        };
+            let section = page_service.get_section(&id).await?;
+            let (page, proof) = authorize_update_page(ctx, &*page_service, &section.page_id).await?;
 
-        let section = page_service.get_section(&id).await?;
-        let page = page_service.get(&section.page_id).await?;
-        user.policy.check_update_page(&page.author_id)?;
+            page_service
+                .move_section(&page.id, &id, direction, proof)
+                .await?;
+            publish_page_event(ctx, &page.id, PageEventKind::SectionReordered, Some(id)).await;
+            Ok(true)
+        })
+        .await
+    }
 
-        page_service
-            .move_section(&page.id, &id, direction)
-            .await
-            .map(|_| true)
+    /// Apply several section operations atomically: resolves and authorizes the owning page
+    /// once, validates every operation up front, then runs them in a single transaction that
+    /// rolls back entirely if any operation fails.
+    async fn batch_update_page_sections(
+        ctx: &Context,
+        page_id: ID,
+        operations: Vec<BatchSectionOperationInput>,
+    ) -> Result<Vec<BatchSectionOperationResult>> {
+        instrumented(ctx, "batch_update_page_sections", async move {
+            let page_service = require_page_service(ctx)?;
+            for op in &operations {
+                op.validate()?;
+            }
+
+            let (_page, proof) = authorize_update_page(ctx, &*page_service, &page_id).await?;
+
+            let results = page_service
+                .apply_section_batch(&page_id, operations.clone(), proof)
+                .await?;
+            for op in &operations {
+                let kind = match op.kind {
+                    BatchSectionOperationKind::UpdateTitle
+                    | BatchSectionOperationKind::UpdateContent => PageEventKind::SectionUpdated,
+                    BatchSectionOperationKind::Delete => PageEventKind::SectionDeleted,
+                    BatchSectionOperationKind::Move => PageEventKind::SectionReordered,
+                };
+                publish_page_event(ctx, &page_id, kind, Some(op.id.clone())).await;
+            }
+            Ok(results)
+        })
+        .await
     }
 
     async fn create_custom_document(ctx: &Context, input: CreateCustomDocumentInput) -> Result<ID> {
-        check_admin(ctx).await?;
-        input.validate()?;
-        let id = ctx
-            .locator
-            .web_documents()
-            .create_custom_web_document(input.name, input.url)
-            .await?;
-        Ok(id)
+        instrumented(ctx, "create_custom_document", async move {
+            let admin = check_admin(ctx).await?;
+            input.validate()?;
+            let id = admin
+                .locator()
+                .web_documents()
+                .create_custom_web_document(input.name, input.url)
+                .await?;
+            record_audit_log(
+                ctx,
+                AuditAction::CustomDocumentCreated,
+                Some(id.to_string()),
+                "Created custom document".into(),
+            )
+            .await;
+            Ok(id)
+        })
+        .await
     }
 
     async fn delete_custom_document(ctx: &Context, id: ID) -> Result<bool> {
-        check_admin(ctx).await?;
-        ctx.locator
-            .web_documents()
-            .delete_custom_web_document(id)
-            .await?;
-        Ok(true)
+        instrumented(ctx, "delete_custom_document", async move {
+            let admin = check_admin(ctx).await?;
+            admin.locator()
+                .web_documents()
+                .delete_custom_web_document(id.clone())
+                .await?;
+            record_audit_log(
+                ctx,
+                AuditAction::CustomDocumentDeleted,
+                Some(id.to_string()),
+                "Deleted custom document".into(),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
     }
 
     async fn set_preset_document_active(
         ctx: &Context,
         input: SetPresetDocumentActiveInput,
     ) -> Result<bool> {
-        check_admin(ctx).await?;
-        input.validate()?;
-        ctx.locator
-            .web_documents()
-            .set_preset_web_documents_active(input.id, input.active)
-            .await?;
-        Ok(true)
+        instrumented(ctx, "set_preset_document_active", async move {
+            let admin = check_admin(ctx).await?;
+            input.validate()?;
+            let id = input.id.clone();
+            let active = input.active;
+            admin.locator()
+                .web_documents()
+                .set_preset_web_documents_active(input.id, input.active)
+                .await?;
+            record_audit_log(
+                ctx,
+                AuditAction::PresetDocumentActiveSet,
+                Some(id.to_string()),
+                format!("Set preset document active to {active}"),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
     }
 
     async fn create_user_group(ctx: &Context, input: CreateUserGroupInput) -> Result<ID> {
-        check_admin(ctx).await?;
-        input.validate()?;
-        let id = ctx.locator.user_group().create(&input).await?;
-        Ok(id)
+        instrumented(ctx, "create_user_group", async move {
+            let admin = check_admin(ctx).await?;
+            input.validate()?;
+            let id = admin.locator().user_group().create(&input).await?;
+            record_audit_log(
+                ctx,
+                AuditAction::UserGroupCreated,
+                Some(id.to_string()),
+                "Created user group".into(),
+            )
+            .await;
+            Ok(id)
+        })
+        .await
     }
 
     async fn delete_user_group(ctx: &Context, id: ID) -> Result<bool> {
-        check_admin(ctx).await?;
-        ctx.locator.user_group().delete(&id).await?;
-        Ok(true)
+        instrumented(ctx, "delete_user_group", async move {
+            let admin = check_admin(ctx).await?;
+            let result = admin.locator().user_group().delete(&id).await;
+            fire_mutation_hook(
+                ctx,
+                AuditAction::UserGroupDeleted,
+                "delete_user_group",
+                Some(id.to_string()),
+                "Deleted user group".into(),
+                &result,
+            )
+            .await;
+            result?;
+            Ok(true)
+        })
+        .await
     }
 
     async fn upsert_user_group_membership(
         ctx: &Context,
         input: UpsertUserGroupMembershipInput,
     ) -> Result<bool> {
-        let user = check_user(ctx).await?;
-        user.policy
-            .check_upsert_user_group_membership(&input)
-            .await?;
+        instrumented(ctx, "upsert_user_group_membership", async move {
+            let UserCtx(_, locator) = check_user(ctx).await?;
+            check_policy(ctx, "upsert_user_group_membership", PolicyResource::new()).await?;
 
-        input.validate()?;
-        ctx.locator.user_group().upsert_membership(&input).await?;
-        Ok(true)
+            input.validate()?;
+            locator.user_group().upsert_membership(&input).await?;
+            Ok(true)
+        })
+        .await
     }
 
     async fn delete_user_group_membership(
@@ -1728,16 +3299,24 @@ impl Mutation {
         user_group_id: ID,
         user_id: ID,
     ) -> Result<bool> {
-        let user = check_user(ctx).await?;
-        user.policy
-            .check_delete_user_group_membership(&user_group_id, &user_id)
+        instrumented(ctx, "delete_user_group_membership", async move {
+            let UserCtx(_, locator) = check_user(ctx).await?;
+            check_policy(
+                ctx,
+                "delete_user_group_membership",
+                PolicyResource::new()
+                    .with("user_group_id", user_group_id.to_string())
+                    .with("user_id", user_id.to_string()),
+            )
             .await?;
 
-        ctx.locator
-            .user_group()
-            .delete_membership(&user_group_id, &user_id)
-            .await?;
-        Ok(true)
+            locator
+                .user_group()
+                .delete_membership(&user_group_id, &user_id)
+                .await?;
+            Ok(true)
+        })
+        .await
     }
 
     async fn grant_source_id_read_access(
@@ -1745,12 +3324,33 @@ impl Mutation {
         source_id: String,
         user_group_id: ID,
     ) -> Result<bool> {
-        check_admin(ctx).await?;
-        ctx.locator
-            .access_policy()
-            .grant_source_id_read_access(&source_id, &user_group_id)
+        instrumented(ctx, "grant_source_id_read_access", async move {
+            let UserCtx(_, locator) = check_user(ctx).await?;
+            check_policy(
+                ctx,
+                "grant_source_read",
+                PolicyResource::new()
+                    .with("source_id", source_id.clone())
+                    .with("user_group_id", user_group_id.to_string()),
+            )
             .await?;
-        Ok(true)
+            let result = locator
+                .access_policy()
+                .grant_source_id_read_access(&source_id, &user_group_id)
+                .await;
+            fire_mutation_hook(
+                ctx,
+                AuditAction::SourceReadAccessGranted,
+                "grant_source_id_read_access",
+                Some(source_id.clone()),
+                format!("Granted user group {user_group_id} read access to source {source_id}"),
+                &result,
+            )
+            .await;
+            result?;
+            Ok(true)
+        })
+        .await
     }
 
     async fn revoke_source_id_read_access(
@@ -1758,12 +3358,61 @@ impl Mutation {
         source_id: String,
         user_group_id: ID,
     ) -> Result<bool> {
-        check_admin(ctx).await?;
-        ctx.locator
-            .access_policy()
-            .revoke_source_id_read_access(&source_id, &user_group_id)
+        instrumented(ctx, "revoke_source_id_read_access", async move {
+            let UserCtx(_, locator) = check_user(ctx).await?;
+            check_policy(
+                ctx,
+                "revoke_source_read",
+                PolicyResource::new()
+                    .with("source_id", source_id.clone())
+                    .with("user_group_id", user_group_id.to_string()),
+            )
             .await?;
-        Ok(true)
+            locator
+                .access_policy()
+                .revoke_source_id_read_access(&source_id, &user_group_id)
+                .await?;
+            record_audit_log(
+                ctx,
+                AuditAction::SourceReadAccessRevoked,
+                Some(source_id.clone()),
+                format!("Revoked user group {user_group_id} read access to source {source_id}"),
+            )
+            .await;
+            Ok(true)
+        })
+        .await
+    }
+
+    /// Diffs the declarative access-control document against current `user_group()` /
+    /// `access_policy()` state and applies the minimal set of changes needed to reach it (the
+    /// same computation a startup reconciliation pass runs automatically). With `dry_run` set,
+    /// only returns the plan without applying it.
+    async fn reconcile_access_policy(
+        ctx: &Context,
+        dry_run: bool,
+    ) -> Result<AccessPolicyReconciliationResult> {
+        instrumented(ctx, "reconcile_access_policy", async move {
+            let admin = check_admin(ctx).await?;
+            let result = admin.locator().access_reconciler().reconcile(dry_run).await?;
+            if !dry_run && !result.changes.is_empty() {
+                let descriptions: Vec<_> = result
+                    .changes
+                    .iter()
+                    .map(|change| change.description.clone())
+                    .collect();
+                record_audit_log_with_metadata(
+                    ctx,
+                    AuditAction::AccessPolicyReconciled,
+                    None,
+                    format!("Applied {} access policy change(s)", result.changes.len()),
+                    Some(serde_json::json!({ "changes": descriptions }).to_string()),
+                )
+                .await;
+            }
+            Ok(result)
+        })
+        .await
     }
 }
 
@@ -1811,69 +3460,78 @@ impl Subscription {
         ctx: &Context,
         input: CreateThreadAndRunInput,
     ) -> Result<ThreadRunStream> {
-        let user = check_user_allow_auth_token(ctx).await?;
-        input.validate()?;
-
-        let thread = ctx.locator.thread();
-
-        let thread_id = thread.create(&user.id, &input.thread).await?;
-
-        thread
-            .create_run(
-                &user,
-                &thread_id,
-                &input.options,
-                input.thread.user_message.attachments.as_ref(),
-                true,
-                true,
-            )
-            .await
+        instrumented(ctx, "create_thread_and_run", async move {
+            let AuthTokenCtx(user, locator) = check_user_with_scope(ctx, Scope::ThreadsWrite).await?;
+            input.validate()?;
+
+            let thread = locator.thread();
+
+            let thread_id = thread.create(&user.id, &input.thread).await?;
+
+            thread
+                .create_run(
+                    &user,
+                    &thread_id,
+                    &input.options,
+                    input.thread.user_message.attachments.as_ref(),
+                    true,
+                    true,
+                )
+                .await
+        })
+        .await
     }
 
     async fn create_thread_run(
         ctx: &Context,
         input: CreateThreadRunInput,
     ) -> Result<ThreadRunStream> {
-        let user = check_user_allow_auth_token(ctx).await?;
-        input.validate()?;
-
-        let svc = ctx.locator.thread();
-        let Some(thread) = svc.get(&input.thread_id).await? else {
-            return Err(CoreError::NotFound("Thread not found"));
-        };
-
-        if thread.user_id != user.id {
-            return Err(CoreError::Forbidden(
-                "You must be the thread owner to create a run",
-            ));
-        }
+        instrumented(ctx, "create_thread_run", async move {
+            let AuthTokenCtx(user, locator) = check_user_with_scope(ctx, Scope::ThreadsWrite).await?;
+            input.validate()?;
+
+            let svc = locator.thread();
+            let Some(thread) = svc.get(&input.thread_id).await? else {
+                return Err(CoreError::NotFound("Thread not found"));
+            };
+
+            if thread.user_id != user.id {
+                return Err(CoreError::Forbidden(
+                    "You must be the thread owner to create a run",
+                ));
+            }
 
-        svc.append_user_message(&input.thread_id, &input.additional_user_message)
-            .await?;
+            svc.append_user_message(&input.thread_id, &input.additional_user_message)
+                .await?;
 
-        svc.create_run(
-            &user,
-            &input.thread_id,
-            &input.options,
-            input.additional_user_message.attachments.as_ref(),
-            true,
-            false,
-        )
+            svc.create_run(
+                &user,
+                &input.thread_id,
+                &input.options,
+                input.additional_user_message.attachments.as_ref(),
+                true,
+                false,
+            )
+            .await
+        })
         .await
     }
 
     async fn create_page_run(ctx: &Context, input: CreatePageRunInput) -> Result<PageRunStream> {
-        let user = check_user(ctx).await?;
+        instrumented(ctx, "create_page_run", async move {
+            let UserCtx(user, locator) = check_user(ctx).await?;
 
-        let page_service = if let Some(service) = ctx.locator.page() {
-            service
-        } else {
-            return Err(CoreError::Forbidden("Page service is not enabled"));
-        };
+            let page_service = if let Some(service) = locator.page() {
+                service
+            } else {
+                return Err(CoreError::Forbidden("Page service is not enabled"));
+            };
 
-        page_service
-            .create_run(&user.policy, &user.id, &input)
-            .await
+            page_service
+                .create_run(&user.policy, &user.id, &input)
+                .await
+        })
+        .await
     }
 
     /// Utilize an existing thread and its messages to create a page.
@@ -1884,35 +3542,65 @@ impl Subscription {
         ctx: &Context,
         input: CreateThreadToPageRunInput,
     ) -> Result<ThreadToPageRunStream> {
-        let user = check_user(ctx).await?;
+        instrumented(ctx, "create_thread_to_page_run", async move {
+            let UserCtx(user, locator) = check_user(ctx).await?;
 
-        let page_service = if let Some(service) = ctx.locator.page() {
-            service
-        } else {
-            return Err(CoreError::Forbidden("Page service is not enabled"));
-        };
+            let page_service = if let Some(service) = locator.page() {
+                service
+            } else {
+                return Err(CoreError::Forbidden("Page service is not enabled"));
+            };
 
-        page_service
-            .convert_thread_to_page(&user.policy, &user.id, &input)
-            .await
+            page_service
+                .convert_thread_to_page(&user.policy, &user.id, &input)
+                .await
+        })
+        .await
     }
 
     async fn create_page_section_run(
         ctx: &Context,
         input: CreatePageSectionRunInput,
     ) -> Result<SectionRunStream> {
-        let user = check_user(ctx).await?;
-
-        let page_service = if let Some(service) = ctx.locator.page() {
-            service
-        } else {
-            return Err(CoreError::Forbidden("Page service is not enabled"));
-        };
-
-        let page = page_service.get(&input.page_id).await?;
-        user.policy.check_update_page(&page.author_id)?;
+        instrumented(ctx, "create_page_section_run", async move {
+            let UserCtx(user, locator) = check_user(ctx).await?;
+
+            let page_service = if let Some(service) = locator.page() {
+                service
+            } else {
+                return Err(CoreError::Forbidden("Page service is not enabled"));
+            };
+
+            let page = page_service.get(&input.page_id).await?;
+            user.policy.check_update_page(&page.author_id)?;
+
+            let stream = page_service.append_section(&user.policy, &input).await?;
+            // The new section's id isn't known synchronously -- it's produced as part of the
+            // streamed run, not returned up front -- so this is published with `section_id: None`,
+            // same as `TitleChanged`/`ContentChanged` above.
+            publish_page_event(ctx, &input.page_id, PageEventKind::SectionAdded, None).await;
+            Ok(stream)
+        })
+        .await
+    }
 
-        page_service.append_section(&user.policy, &input).await
+    /// Streams change events (section added/updated/reordered/deleted, title/content changed)
+    /// for one page, so multiple collaborators viewing it can see each other's edits without
+    /// polling. Gated by the same check as the `pages`/`page_sections` queries: any logged-in
+    /// user may subscribe.
+    async fn page_events(ctx: &Context, page_id: ID) -> Result<PageEventStream> {
+        instrumented(ctx, "page_events", async move {
+            // Same access check as the `pages`/`page_sections` queries: any logged-in user can
+            // read a page, so subscribing to its live updates needs nothing stricter than that.
+            // `authorize_update_page` runs the `update_page` policy (ownership, under the
+            // built-in engine) and would reject a viewer who can read the page but isn't its
+            // editor.
+            let UserCtx(_, locator) = check_user(ctx).await?;
+            require_page_service(ctx)?;
+
+            Ok(locator.page_events().subscribe(&page_id).await)
+        })
+        .await
     }
 }
 
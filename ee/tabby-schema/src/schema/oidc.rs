@@ -0,0 +1,38 @@
+use async_trait::async_trait;
+use juniper::{GraphQLInputObject, GraphQLObject};
+use validator::Validate;
+
+use super::two_factor::TokenAuthOutcome;
+use super::Result;
+
+/// A configured generic OIDC identity provider, used to offer SSO login alongside
+/// the provider-specific OAuth credentials.
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct OidcCredential {
+    pub issuer_url: String,
+    pub client_id: String,
+}
+
+#[derive(GraphQLInputObject, Validate)]
+pub struct UpdateOidcCredentialInput {
+    #[validate(url)]
+    pub issuer_url: String,
+    #[validate(length(min = 1))]
+    pub client_id: String,
+    #[validate(length(min = 1))]
+    pub client_secret: String,
+}
+
+#[async_trait]
+pub trait OidcService: Send + Sync {
+    async fn read_credential(&self) -> Result<Option<OidcCredential>>;
+    async fn update_credential(&self, input: UpdateOidcCredentialInput) -> Result<()>;
+    async fn delete_credential(&self) -> Result<()>;
+
+    /// The URL Tabby's OIDC callback handler is served at, for the admin to register
+    /// with their identity provider.
+    async fn callback_url(&self) -> Result<String>;
+
+    /// Exchange an authorization code for tokens, completing the SSO login.
+    async fn exchange_code(&self, code: String, state: String) -> Result<TokenAuthOutcome>;
+}
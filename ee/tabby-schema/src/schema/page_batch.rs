@@ -0,0 +1,103 @@
+use juniper::{GraphQLEnum, GraphQLInputObject, GraphQLObject, ID};
+use validator::{Validate, ValidationError};
+
+use super::page::MoveSectionDirection;
+
+#[derive(GraphQLEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BatchSectionOperationKind {
+    UpdateTitle,
+    UpdateContent,
+    Delete,
+    Move,
+}
+
+/// One operation within a `batch_update_page_sections` call. GraphQL has no tagged union for
+/// inputs, so this flattens the variants into one struct with a `kind` discriminator and the
+/// fields relevant to it; `validate()` rejects a `kind` whose required field is missing.
+#[derive(GraphQLInputObject, Validate, Clone, Debug)]
+#[validate(schema(function = "validate_operation_has_required_field"))]
+pub struct BatchSectionOperationInput {
+    pub id: ID,
+    pub kind: BatchSectionOperationKind,
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub direction: Option<MoveSectionDirection>,
+}
+
+fn validate_operation_has_required_field(
+    input: &BatchSectionOperationInput,
+) -> Result<(), ValidationError> {
+    let has_required_field = match input.kind {
+        BatchSectionOperationKind::UpdateTitle => input.title.is_some(),
+        BatchSectionOperationKind::UpdateContent => input.content.is_some(),
+        BatchSectionOperationKind::Delete => true,
+        BatchSectionOperationKind::Move => input.direction.is_some(),
+    };
+    if has_required_field {
+        Ok(())
+    } else {
+        Err(ValidationError::new("missing_field_for_operation_kind"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(kind: BatchSectionOperationKind) -> BatchSectionOperationInput {
+        BatchSectionOperationInput {
+            id: ID::from("1".to_string()),
+            kind,
+            title: None,
+            content: None,
+            direction: None,
+        }
+    }
+
+    #[test]
+    fn delete_never_requires_a_field() {
+        assert!(validate_operation_has_required_field(&input(BatchSectionOperationKind::Delete)).is_ok());
+    }
+
+    #[test]
+    fn update_title_requires_title() {
+        assert!(
+            validate_operation_has_required_field(&input(BatchSectionOperationKind::UpdateTitle))
+                .is_err()
+        );
+        let mut op = input(BatchSectionOperationKind::UpdateTitle);
+        op.title = Some("t".to_string());
+        assert!(validate_operation_has_required_field(&op).is_ok());
+    }
+
+    #[test]
+    fn update_content_requires_content() {
+        assert!(validate_operation_has_required_field(&input(
+            BatchSectionOperationKind::UpdateContent
+        ))
+        .is_err());
+        let mut op = input(BatchSectionOperationKind::UpdateContent);
+        op.content = Some("c".to_string());
+        assert!(validate_operation_has_required_field(&op).is_ok());
+    }
+
+    #[test]
+    fn move_requires_direction() {
+        assert!(
+            validate_operation_has_required_field(&input(BatchSectionOperationKind::Move)).is_err()
+        );
+        let mut op = input(BatchSectionOperationKind::Move);
+        op.direction = Some(MoveSectionDirection::Up);
+        assert!(validate_operation_has_required_field(&op).is_ok());
+    }
+}
+
+/// Confirms one operation that was applied within a `batch_update_page_sections` call. The
+/// batch runs as a single transaction that rolls back entirely on any error (the mutation
+/// itself returns that error instead), so a `BatchSectionOperationResult` only ever exists for
+/// an operation that actually succeeded -- there is no per-operation failure to report here.
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct BatchSectionOperationResult {
+    pub id: ID,
+    pub kind: BatchSectionOperationKind,
+}
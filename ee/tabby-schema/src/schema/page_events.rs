@@ -0,0 +1,38 @@
+use std::pin::Pin;
+
+use async_trait::async_trait;
+use futures::Stream;
+use juniper::{GraphQLEnum, GraphQLObject, ID};
+
+use super::Result;
+
+/// What changed about a page, mirroring the write mutations that can touch it.
+#[derive(GraphQLEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PageEventKind {
+    TitleChanged,
+    ContentChanged,
+    SectionAdded,
+    SectionUpdated,
+    SectionReordered,
+    SectionDeleted,
+}
+
+/// A single change to a page, broadcast to every `page_events(page_id)` subscriber watching it.
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct PageEvent {
+    pub page_id: ID,
+    pub kind: PageEventKind,
+    pub section_id: Option<ID>,
+}
+
+pub type PageEventStream = Pin<Box<dyn Stream<Item = Result<PageEvent>> + Send>>;
+
+/// Fans a page's write mutations out to every connected `page_events` subscriber, keyed by page
+/// id. Mutation resolvers call `publish` after a successful write; the `page_events`
+/// subscription resolver calls `subscribe` once, after checking the caller can read the page.
+#[async_trait]
+pub trait PageEventBroker: Send + Sync {
+    async fn publish(&self, page_id: &ID, event: PageEvent);
+
+    async fn subscribe(&self, page_id: &ID) -> PageEventStream;
+}
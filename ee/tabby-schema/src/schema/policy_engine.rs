@@ -0,0 +1,76 @@
+use async_trait::async_trait;
+
+use super::Result;
+
+/// Who is asking: the authenticated user plus the groups they belong to, so an external engine
+/// can apply group-based rules without Tabby needing to know what those rules are.
+#[derive(Clone, Debug)]
+pub struct PolicySubject {
+    pub user_id: String,
+    pub group_ids: Vec<String>,
+}
+
+/// What they're asking to do, e.g. `"update_page"` or `"grant_source_read"`.
+pub type PolicyAction = &'static str;
+
+/// What they're asking to do it to, as loosely-typed attributes (author id, source id, ...) so
+/// new resource shapes don't require widening this type.
+#[derive(Clone, Debug, Default)]
+pub struct PolicyResource {
+    pub attributes: Vec<(&'static str, String)>,
+}
+
+impl PolicyResource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, key: &'static str, value: impl Into<String>) -> Self {
+        self.attributes.push((key, value.into()));
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PolicyDecision {
+    Allow,
+    Deny,
+}
+
+/// The authorization decision for every action `check_policy` guards (`update_page`,
+/// `grant_source_read`, `revoke_source_read`, `upsert_user_group_membership`,
+/// `delete_user_group_membership`, ...) -- not a second opinion layered on top of a hardcoded
+/// check that already ran. The built-in implementation re-evaluates the same rules Tabby used to
+/// hardcode (ownership for pages, admin-only for source grants); operators can swap in a client
+/// for an external ABAC/RBAC engine to change that decision instead.
+#[async_trait]
+pub trait PolicyEngine: Send + Sync {
+    async fn evaluate(
+        &self,
+        subject: PolicySubject,
+        action: PolicyAction,
+        resource: PolicyResource,
+    ) -> Result<PolicyDecision>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_appends_attributes_in_order() {
+        let resource = PolicyResource::new()
+            .with("author_id", "u1")
+            .with("source_id", "s1".to_string());
+
+        assert_eq!(
+            resource.attributes,
+            vec![("author_id", "u1".to_string()), ("source_id", "s1".to_string())]
+        );
+    }
+
+    #[test]
+    fn new_has_no_attributes() {
+        assert!(PolicyResource::new().attributes.is_empty());
+    }
+}
@@ -0,0 +1,59 @@
+use juniper::GraphQLObject;
+
+use super::auth::TokenAuthResponse;
+
+/// The outcome of a credential check, before the GraphQL layer decides how to present it.
+///
+/// Kept separate from [`TokenAuthResult`] because most callers (the auth service, the OIDC
+/// service) only care about whether a second factor is still owed, not about wire encoding.
+pub enum TokenAuthOutcome {
+    Authenticated(TokenAuthResponse),
+    TwoFactorRequired { pending_session: String },
+}
+
+impl From<TokenAuthOutcome> for TokenAuthResult {
+    fn from(outcome: TokenAuthOutcome) -> Self {
+        match outcome {
+            TokenAuthOutcome::Authenticated(token) => TokenAuthResult {
+                token: Some(token),
+                pending_totp_session: None,
+            },
+            TokenAuthOutcome::TwoFactorRequired { pending_session } => TokenAuthResult {
+                token: None,
+                pending_totp_session: Some(pending_session),
+            },
+        }
+    }
+}
+
+/// Returned by every login mutation (`tokenAuth`, `tokenAuthLdap`, `tokenAuthOidc`) in place of
+/// a bare `TokenAuthResponse`, so a client can distinguish "logged in" from "enter your TOTP
+/// code" without a separate round trip to check.
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct TokenAuthResult {
+    pub token: Option<TokenAuthResponse>,
+    pub pending_totp_session: Option<String>,
+}
+
+/// A freshly generated TOTP secret, not yet confirmed by `enable_totp`.
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct TotpSecret {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+/// One-time recovery codes issued when TOTP is first enabled; each can be exchanged for a
+/// single successful login if the authenticator device is lost.
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct TotpRecoveryCodes {
+    pub codes: Vec<String>,
+}
+
+/// Result of updating the security setting. `locked_out_user_count` is the number of users
+/// without TOTP when `require_two_factor` is on (0 otherwise); the setting is already saved by
+/// the time this is returned.
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct UpdateSecuritySettingResult {
+    pub ok: bool,
+    pub locked_out_user_count: i32,
+}